@@ -2,64 +2,1245 @@
 ///
 /// This module just contains the logic for running the main.rs application.
 ///
-use std::path::Path;
+use std::fmt;
+use std::collections::HashSet;
+use std::fs;
+use std::panic;
+use std::str::FromStr;
+use std::path::{Path, PathBuf};
 use std::thread::sleep;
-use std::time::Duration;
-use std::io::{self, Write};
+use std::time::{Duration, Instant};
+use std::io::{self, Read, Stdout, Write};
+
+use serde::{Deserialize, Serialize};
+use termion::cursor::HideCursor;
+use termion::event::Key;
+use termion::input::TermRead;
+use termion::raw::{IntoRawMode, RawTerminal};
+use termion::screen::AlternateScreen;
 
 use crate::{board, config};
+use crate::cycle::CycleDetector;
+use crate::rule::Rule;
+use crate::topology::{EdgeMode, Topology};
+
+/// When the animation should stop on its own
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ExitOn {
+    /// Once every cell is dead
+    Death,
+    /// Once the board stops changing from one generation to the next, or dies
+    Stabilize,
+    /// Keep going until the user quits
+    Never,
+}
+
+impl FromStr for ExitOn {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<ExitOn, String> {
+        match s.to_ascii_lowercase().as_str() {
+            "death" => Ok(ExitOn::Death),
+            "stabilize" => Ok(ExitOn::Stabilize),
+            "never" => Ok(ExitOn::Never),
+            _ => Err(format!("Unknown exit condition: {}", s)),
+        }
+    }
+}
+
+/// Where the size of the board came from, from the highest precedence to the lowest
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SizeSource {
+    /// `-r`, `-c` or `--size` on the command line. Patterns that don't fit are an error
+    Flag,
+    /// The smallest size the loaded configuration fits in, where that's bigger than the fallback
+    Config,
+    /// `--fit`, filling the terminal
+    Fit,
+    /// The built in 80x40
+    Default,
+}
 
-pub fn app(rows: usize, cols: usize, prob_density: Option<f32>, init_filepath: Option<&Path>, update_rate: Duration) {
-    let mut rows = rows;
-    let mut cols = cols;
+impl fmt::Display for SizeSource {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            SizeSource::Flag => "command line",
+            SizeSource::Config => "pattern size",
+            SizeSource::Fit => "terminal size",
+            SizeSource::Default => "default",
+        })
+    }
+}
+
+/// Everything the application needs to know to run, as given on the command line
+pub struct Options<'a> {
+    pub rows: usize,
+    pub cols: usize,
+    /// Where `rows` and `cols` came from, which decides whether a configuration can grow them
+    pub size_source: SizeSource,
+    pub prob_density: Option<f32>,
+    pub init_filepath: Option<&'a Path>,
+    /// Name of a pattern built into the crate to start from, see `Configuration::builtin`
+    pub pattern: Option<&'a str>,
+    /// Scene manifest to build the board from, see `load_scene`
+    pub scene: Option<&'a Path>,
+    /// Read the configuration from stdin instead of a file
+    pub stdin: bool,
+    /// Format of the configuration on stdin. Inferred from its contents if not given
+    pub format: Option<config::Format>,
+    pub update_rate: Duration,
+    /// Number of generations to advance between each drawn frame
+    pub render_every: usize,
+    /// Frames to draw per second instead of waiting `update_rate` between them. When a frame
+    /// takes too long, the next one advances a generation for every frame that was missed
+    pub target_fps: Option<u32>,
+    /// Start over from the first generation once the board dies or stabilizes
+    pub replay: bool,
+    /// When to stop on its own. Ignored if `replay` is set
+    pub exit_on: ExitOn,
+    /// Also stop once there are no alive cells in rows `r0..r1` and columns `c0..c1`, given as
+    /// (r0, c0, r1, c1)
+    pub watch_region: Option<(usize, usize, usize, usize)>,
+    /// Print the first generation as plain text and exit instead of animating
+    pub once: bool,
+    /// Run this many generations without drawing, then print the last one as plain text
+    pub headless: Option<usize>,
+    /// Print the generation and population to stderr every this many generations of a
+    /// `headless` run
+    pub progress_every: Option<usize>,
+    /// Stop a `headless` run after any generation that takes longer than this
+    pub step_timeout: Option<Duration>,
+    /// Print every frame as plain text after the last one instead of redrawing the screen,
+    /// for capturing a run to a file
+    pub append: bool,
+    /// Print every generation as a line of JSON instead of drawing it, see `write_json_frame`
+    pub json_stream: bool,
+    /// Let the user draw on the board before the simulation starts
+    pub edit: bool,
+    pub ghost: bool,
+    /// Color every cell by how many alive neighbors it has
+    pub heatmap: bool,
+    /// Mark every cell that has ever been alive in a dim color
+    pub trail: bool,
+    /// Draw alive cells as their number of alive neighbors
+    pub show_counts: bool,
+    /// Cells from across wrapped edges to show outside the border
+    pub wrap_margin: usize,
+    pub topology: Topology,
+    pub edges: EdgeMode,
+    pub force_conway: bool,
+    pub status: bool,
+}
 
+/// returns the (rows, cols) of the board and where they came from
+///
+/// A size from the command line always wins, and it's an error if `conf` doesn't fit in it.
+/// Otherwise the board grows to fit `conf` in either direction it needs to.
+fn resolve_size(
+    rows: usize,
+    cols: usize,
+    source: SizeSource,
+    conf: Option<&config::Configuration>,
+) -> Result<(usize, usize, SizeSource), String> {
+    let c = match conf {
+        Some(c) if c.rows > rows || c.cols > cols => c,
+        _ => return Ok((rows, cols, source)),
+    };
+    if source == SizeSource::Flag {
+        return Err(format!(
+            "Pattern needs at least {} rows and {} cols but the board is {}x{}",
+            c.rows, c.cols, rows, cols
+        ));
+    }
+    Ok((rows.max(c.rows), cols.max(c.cols), SizeSource::Config))
+}
+
+pub fn app(opts: &Options) {
     // initialize with a file (pull out rows/cols first)
     let mut conf: Option<config::Configuration> = None;
-    if let Some(p) = init_filepath {
-        if let Some(ext) = p.extension() {
-            if ext == "json" {
-                conf = Some(config::Configuration::load_json_config(p).unwrap());
-            } else {
-                conf = Some(config::Configuration::load_rle_config(p).unwrap());
-            }
+    if opts.stdin {
+        let mut s = String::new();
+        io::stdin().read_to_string(&mut s).unwrap();
+        let format = match opts.format.or_else(|| config::Format::infer(&s)) {
+            Some(f) => f,
+            None => panic!("Couldn't tell what format stdin is in! Please pass --format json, rle, cells or life106."),
+        };
+        conf = Some(config::Configuration::parse(&s, format).unwrap());
+    } else if let Some(name) = opts.pattern {
+        match config::Configuration::builtin(name) {
+            Some(c) => conf = Some(c),
+            None => panic!("Unknown pattern {}! See --list-patterns.", name),
+        }
+    } else if let Some(p) = opts.init_filepath {
+        if p.extension().is_some() {
+            conf = Some(config::Configuration::load(p).unwrap());
+        }
+    }
+
+    if let Some(c) = conf.as_ref() {
+        if let (Some(header), Some(comment)) = (&c.rule, &c.rule_conflict) {
+            eprintln!("Rule in header ({}) conflicts with #r line ({}), using the header", header, comment);
         }
     }
 
+    let (rows, cols, size_source) = match resolve_size(opts.rows, opts.cols, opts.size_source, conf.as_ref()) {
+        Ok(size) => size,
+        Err(why) => panic!("{}! Pass a bigger size or leave it out.", why),
+    };
+    if !opts.once && opts.headless.is_none() && !opts.json_stream {
+        println!("Board size: rows: {}, cols: {} (from {})", rows, cols, size_source);
+    }
+
     let mut board: board::Board;
 
-    if let Some(c) = conf {
-        if c.cols > cols {
-            cols = c.cols;
+    if let Some(manifest) = opts.scene {
+        board = board::Board::new(rows, cols);
+        if let Err(why) = load_scene(manifest, &mut board) {
+            panic!("Couldn't load scene {}: {}", manifest.display(), why);
         }
-        if c.rows > rows {
-            rows = c.rows;
+    } else if let Some(c) = conf {
+        let rule = c.rule().unwrap();
+        if opts.force_conway && !rule.is_conway() {
+            panic!("Specified life type {} is not Conway! Cannot play config.", rule);
         }
-        println!("Board size: rows: {}, cols: {}", rows, cols);
+
         board = board::Board::new(rows, cols);
         c.apply_config(&mut board).unwrap();
-    } else if let Some(density) = prob_density {
+        board.rule = rule;
+    } else if let Some(density) = opts.prob_density {
         // initialize randomly
         board = board::Board::new(rows, cols);
         board.initialize_random(density);
     } else {
         panic!("Invalid arguments! need either random probability density or configuration file.");
     }
+    board.ghost = opts.ghost;
+    board.heatmap = opts.heatmap;
+    board.trail = opts.trail;
+    board.show_counts = opts.show_counts;
+    board.show_wrap_margin = opts.wrap_margin;
+    board.topology = opts.topology;
+    board.edges = opts.edges;
+
+    if let Some(generations) = opts.headless {
+        let progress: Option<Box<dyn FnMut(usize, usize)>> = match opts.progress_every {
+            Some(_) => Some(Box::new(|gen, alive| eprintln!("gen: {} | alive: {}", gen, alive))),
+            None => None,
+        };
+        let step_timeout = opts.step_timeout;
+        if let Err(why) = run_headless(&mut board, generations, opts.progress_every.unwrap_or(0), step_timeout, progress) {
+            eprintln!("Stopped early: {}", why);
+        }
+    }
+    if opts.once || opts.headless.is_some() {
+        board.write_plain(&mut io::stdout()).unwrap();
+        return;
+    }
+
+    // boards bigger than the terminal are drawn through a viewport that can be
+    // panned with the arrow keys, and that follows the terminal as it's resized
+    let mut term = terminal_area();
+    let mut view = Viewport::fit(&board, term);
+
+    // take over the terminal if we can, so it can be put back cleanly when we're done
+    let guard = if opts.append || opts.json_stream { None } else { TerminalGuard::new() };
+    let mut keys = guard.as_ref().map(|_| termion::async_stdin().keys());
+
+    if opts.edit {
+        match keys.as_mut() {
+            Some(keys) => {
+                if !edit(&mut board, &mut view, keys) {
+                    return;
+                }
+            }
+            None => eprintln!("Can't edit the board without a terminal, starting right away"),
+        }
+    }
+
+    // kept around so the run can be replayed exactly
+    let initial = board.clone();
+    let mut last_tick: Option<board::TickResult> = None;
+
+    let frame_budget = opts.target_fps.map(|fps| Duration::from_secs(1) / fps);
+    let mut per_frame = opts.render_every.max(1);
+    let mut dropped_frames = 0;
+    let mut frame_start = Instant::now();
 
     // continually update screen
     let screen = io::stdout();
-    loop {
+    'running: loop {
         {
             let mut handle = screen.lock();
 
-            // animate on the main screen
-            handle.write_all(format!("{}", board).as_bytes()).unwrap();
+            let status = if opts.status {
+                let speed = match opts.target_fps {
+                    Some(fps) => format!("fps: {} | dropped: {}", fps, dropped_frames),
+                    None => format!("rate: {}ms", opts.update_rate.as_millis()),
+                };
+                Some(format!(
+                    "gen: {} | alive: {} | rule: {} | {}",
+                    board.get_generation(),
+                    board.get_num_alive_cells(),
+                    board.rule(),
+                    speed,
+                ))
+            } else {
+                None
+            };
+
+            if opts.json_stream {
+                // flushed right away so consumers get each generation as soon as it's ready,
+                // and stop quietly once they hang up
+                if write_json_frame(&board, &mut handle).and_then(|_| handle.flush()).is_err() {
+                    break 'running;
+                }
+            } else if opts.append {
+                // one plain frame after another, separated by a blank line
+                board.write_plain(&mut handle).unwrap();
+                if let Some(status) = status {
+                    writeln!(handle, "{}", status).unwrap();
+                }
+                writeln!(handle).unwrap();
+            } else {
+                // animate on the main screen, sized for the terminal as it is now
+                let now = terminal_area();
+                if now != term {
+                    term = now;
+                    view.refit(&board, term);
+                }
+                view.draw(&board, &mut handle).unwrap();
+                if let Some(status) = status {
+                    write!(
+                        handle,
+                        "{}{}\r\n",
+                        termion::cursor::Goto(1, (view.height + 3 + 2 * view.margin(&board)) as u16),
+                        status,
+                    ).unwrap();
+                }
+            }
+            handle.flush().unwrap();
+        }
+
+        let stable = last_tick.is_some_and(|t| !t.changed);
+        let finished = match opts.exit_on {
+            ExitOn::Death => board.is_empty(),
+            ExitOn::Stabilize => board.is_empty() || stable,
+            ExitOn::Never => false,
+        };
+        let left_region = opts.watch_region.is_some_and(|(r0, c0, r1, c1)| board.alive_in_region(r0, c0, r1, c1) == 0);
+        if (finished || left_region) && !opts.replay {
+            break;
+        }
+
+        match frame_budget {
+            Some(budget) => {
+                // catch up on the generations of any frames we were too slow to draw
+                let spent = frame_start.elapsed();
+                let frames = (spent.as_nanos() / budget.as_nanos()) as usize + 1;
+                per_frame = frames.min(MAX_CATCH_UP);
+                dropped_frames += frames - 1;
+                sleep(budget.saturating_sub(spent));
+            }
+            None => sleep(opts.update_rate),
+        }
+        frame_start = Instant::now();
+
+        // the terminal is in raw mode, so Ctrl-C comes in as a key rather than a signal
+        if let Some(keys) = keys.as_mut() {
+            while let Some(Ok(key)) = keys.next() {
+                match key {
+                    Key::Up => view.pan(&board, -1, 0),
+                    Key::Down => view.pan(&board, 1, 0),
+                    Key::Left => view.pan(&board, 0, -1),
+                    Key::Right => view.pan(&board, 0, 1),
+                    Key::Char('q') | Key::Ctrl('c') => break 'running,
+                    _ => {}
+                }
+            }
+        }
+
+        // once the board dies or stops changing, start over from the beginning if asked to
+        if opts.replay && (board.is_empty() || stable) {
+            board = initial.clone();
+            last_tick = None;
+            continue;
+        }
+
+        // advance several generations per frame, but stop early if everything died
+        for _ in 0..per_frame {
+            last_tick = Some(board.tick());
+            if board.is_empty() {
+                break;
+            }
+        }
+    }
+}
+
+/// Lets the user move a cursor around the board with the arrow keys and toggle cells with
+/// space before the simulation starts. Returns false if they quit instead of pressing enter.
+fn edit<I>(board: &mut board::Board, view: &mut Viewport, keys: &mut I) -> bool
+where
+    I: Iterator<Item = io::Result<Key>>,
+{
+    if board.rows == 0 || board.cols == 0 {
+        return true;
+    }
+    let mut row = board.rows / 2;
+    let mut col = board.cols / 2;
+
+    let screen = io::stdout();
+    let mut term = terminal_area();
+    let mut redraw = true;
+    loop {
+        let now = terminal_area();
+        if now != term {
+            term = now;
+            view.refit(board, term);
+            redraw = true;
+        }
+        if redraw {
+            let margin = view.margin(board);
+            view.follow(board, row, col);
+            let mut handle = screen.lock();
+            view.draw(board, &mut handle).unwrap();
+
+            // highlight the cell under the cursor
+            let (x, y) = view.screen_position(board, row, col, margin);
+            write!(
+                handle,
+                "{}{}{}{}",
+                termion::cursor::Goto(x, y),
+                termion::style::Invert,
+                board.grid[row][col].glyph().unwrap_or(' '),
+                termion::style::Reset,
+            ).unwrap();
+            write!(
+                handle,
+                "{}editing: arrows move | space toggles | enter starts | q quits\r\n",
+                termion::cursor::Goto(1, (view.height + 3 + 2 * margin) as u16),
+            ).unwrap();
+            handle.flush().unwrap();
+            redraw = false;
+        }
+
+        match keys.next() {
+            Some(Ok(key)) => {
+                redraw = true;
+                match key {
+                    Key::Up => row = row.saturating_sub(1),
+                    Key::Down => row = (row + 1).min(board.rows - 1),
+                    Key::Left => col = col.saturating_sub(1),
+                    Key::Right => col = (col + 1).min(board.cols - 1),
+                    Key::Char(' ') => board.toggle_cell(row, col).unwrap(),
+                    Key::Char('\n') => return true,
+                    Key::Char('q') | Key::Ctrl('c') => return false,
+                    _ => redraw = false,
+                }
+            }
+            _ => sleep(Duration::from_millis(10)),
+        }
+    }
+}
+
+/// Most generations `--target-fps` will advance in one frame to catch up, so a slow
+/// update can't snowball into ever longer frames
+const MAX_CATCH_UP: usize = 16;
+
+/// returns the (columns, rows) of the terminal, or as big as can be if it can't be told
+fn terminal_area() -> (u16, u16) {
+    termion::terminal_size()
+        .ok()
+        .filter(|(c, r)| *c > 0 && *r > 0)
+        .unwrap_or((u16::MAX, u16::MAX))
+}
+
+/// Switches the terminal to the alternate screen, hides the cursor and puts it in raw mode
+/// for as long as it's alive. Everything is put back when it's dropped, including while
+/// unwinding from a panic.
+struct TerminalGuard {
+    // dropped in this order: raw mode first, then the cursor, then the screen
+    _raw: RawTerminal<Stdout>,
+    _cursor: HideCursor<Stdout>,
+    _screen: AlternateScreen<Stdout>,
+}
+
+impl TerminalGuard {
+    /// Takes over the terminal, or returns `None` if stdin or stdout isn't one.
+    ///
+    /// Both need to be a terminal so that we can read Ctrl-C as a key and clean up,
+    /// otherwise it would kill us with the terminal still taken over.
+    fn new() -> Option<TerminalGuard> {
+        if !termion::is_tty(&io::stdin()) || !termion::is_tty(&io::stdout()) {
+            return None;
+        }
+
+        let guard = TerminalGuard {
+            _raw: io::stdout().into_raw_mode().ok()?,
+            _cursor: HideCursor::from(io::stdout()),
+            _screen: AlternateScreen::from(io::stdout()),
+        };
+
+        // panic messages are printed before the guard is dropped, so get them onto the main screen
+        let default_hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            print!("{}{}", termion::screen::ToMainScreen, termion::cursor::Show);
+            default_hook(info);
+        }));
+
+        Some(guard)
+    }
+}
+
+/// A window into a board that is too big to draw on the terminal all at once
+struct Viewport {
+    /// false if the whole board fits on the terminal
+    needed: bool,
+    top: usize,
+    left: usize,
+    height: usize,
+    width: usize,
+}
+
+impl Viewport {
+    /// Makes a viewport that fits on a terminal of (`term_cols`, `term_rows`) along with the
+    /// border, wrap margin and status line, centered on the alive cells
+    fn fit(board: &board::Board, (term_cols, term_rows): (u16, u16)) -> Viewport {
+        let margin = board.wrap_margin();
+        Viewport::new(
+            board,
+            (term_rows as usize).saturating_sub(3 + 2 * margin),
+            (term_cols as usize).saturating_sub(2 + 2 * margin),
+        )
+    }
+
+    /// Resizes the viewport for a terminal that is now (`term_cols`, `term_rows`), keeping the
+    /// same part of the board in the middle of it
+    fn refit(&mut self, board: &board::Board, term: (u16, u16)) {
+        let center = (self.top + self.height / 2, self.left + self.width / 2);
+        let was_needed = self.needed;
+        *self = Viewport::fit(board, term);
+        if was_needed && self.needed {
+            self.top = center.0.saturating_sub(self.height / 2);
+            self.left = center.1.saturating_sub(self.width / 2);
+            self.pan(board, 0, 0);
+        }
+    }
+
+    /// returns the width of the wrap margin drawn around the board. It isn't drawn around a viewport
+    fn margin(&self, board: &board::Board) -> usize {
+        if self.needed {
+            0
+        } else {
+            board.wrap_margin()
+        }
+    }
+
+    /// Makes a viewport that fits in `height` rows by `screen_cols` terminal columns,
+    /// centered on the alive cells
+    fn new(board: &board::Board, height: usize, screen_cols: usize) -> Viewport {
+        let width = match board.topology {
+            Topology::Moore => screen_cols,
+            Topology::Hex => screen_cols.saturating_sub(1) / 2,
+        };
+        let height = height.min(board.rows);
+        let width = width.min(board.cols);
+        let (center_row, center_col) = match board.live_bounding_box() {
+            Some((t, l, b, r)) => ((t + b) / 2, (l + r) / 2),
+            None => (board.rows / 2, board.cols / 2),
+        };
+
+        let mut view = Viewport {
+            needed: height < board.rows || width < board.cols,
+            top: center_row.saturating_sub(height / 2),
+            left: center_col.saturating_sub(width / 2),
+            height,
+            width,
+        };
+        view.pan(board, 0, 0);
+        view
+    }
+
+    /// Draws the part of the board the viewport shows, or all of it if the viewport isn't needed
+    fn draw<W: Write>(&self, board: &board::Board, w: &mut W) -> io::Result<()> {
+        if self.needed {
+            board.render_viewport(self.top, self.left, self.height, self.width, w)
+        } else {
+            board.write_to(w)
+        }
+    }
+
+    /// returns the terminal (column, row) the cell at (`row`, `col`) is drawn at. The cell
+    /// has to be in view, and `margin` is the wrap margin around a board drawn without a viewport
+    fn screen_position(&self, board: &board::Board, row: usize, col: usize, margin: usize) -> (u16, u16) {
+        if self.needed {
+            (board.screen_x(row, col - self.left), (row - self.top) as u16 + 2)
+        } else {
+            (board.screen_x(row, col) + margin as u16, (row + margin) as u16 + 2)
+        }
+    }
+
+    /// Pans just far enough to bring the cell at (`row`, `col`) into view
+    fn follow(&mut self, board: &board::Board, row: usize, col: usize) {
+        if row < self.top {
+            self.top = row;
+        } else if row >= self.top + self.height {
+            self.top = row + 1 - self.height;
+        }
+        if col < self.left {
+            self.left = col;
+        } else if col >= self.left + self.width {
+            self.left = col + 1 - self.width;
+        }
+        self.pan(board, 0, 0);
+    }
+
+    /// Moves the viewport by the given number of rows and columns, keeping it on the board
+    fn pan(&mut self, board: &board::Board, rows: isize, cols: isize) {
+        let max_top = board.rows - self.height;
+        let max_left = board.cols - self.width;
+        self.top = ((self.top as isize + rows).max(0) as usize).min(max_top);
+        self.left = ((self.left as isize + cols).max(0) as usize).min(max_left);
+    }
+}
+
+/// Advances `board` one generation every `rate` for `generations` generations, calling
+/// `callback` with the board after each one. Returns the board once it's done.
+///
+/// Waiting between generations yields to the tokio runtime rather than blocking the thread.
+#[cfg(feature = "tokio")]
+pub async fn run_async<F>(mut board: board::Board, rate: Duration, generations: usize, mut callback: F) -> board::Board
+where
+    F: FnMut(&board::Board),
+{
+    let mut interval = tokio::time::interval(rate);
+    // the first tick finishes right away, so the first generation happens a full `rate` in
+    interval.tick().await;
+    for _ in 0..generations {
+        interval.tick().await;
+        board.update();
+        callback(&board);
+    }
+    board
+}
+
+/// One generation of a `--json-stream`
+#[derive(Serialize)]
+struct JsonFrame {
+    generation: usize,
+    alive: Vec<(usize, usize)>,
+}
+
+/// Writes the board's generation and alive cells as a single line of JSON, like
+/// `{"generation":3,"alive":[[0,1],[1,2]]}`, for streaming to other programs one line per
+/// generation. Cells are (row, col) in row major order.
+pub fn write_json_frame<W: Write>(board: &board::Board, w: &mut W) -> io::Result<()> {
+    let frame = JsonFrame {
+        generation: board.get_generation(),
+        alive: board.iter_cells().filter(|(_, alive)| **alive).map(|(rc, _)| rc).collect(),
+    };
+    serde_json::to_writer(&mut *w, &frame)?;
+    writeln!(w)
+}
+
+/// Advances `board` by `generations` generations without drawing anything
+///
+/// Every `progress_every` generations, and after the last one, `progress` is called with the
+/// generation and the number of alive cells, for keeping an eye on long runs. A
+/// `progress_every` of 0 only reports the end.
+///
+/// If a single generation takes longer than `step_timeout`, the run stops right after it and
+/// says which one it was, so an unknown pattern that grows out of hand can't run forever.
+pub fn run_headless(
+    board: &mut board::Board,
+    generations: usize,
+    progress_every: usize,
+    step_timeout: Option<Duration>,
+    mut progress: Option<Box<dyn FnMut(usize, usize) + '_>>,
+) -> Result<(), String> {
+    for n in 1..=generations {
+        let start = Instant::now();
+        board.update();
+        let took = start.elapsed();
+        if let Some(report) = progress.as_mut() {
+            if n == generations || (progress_every > 0 && n % progress_every == 0) {
+                report(board.get_generation(), board.get_num_alive_cells());
+            }
+        }
+        if let Some(limit) = step_timeout.filter(|limit| took > *limit) {
+            return Err(format!(
+                "generation {} took {}ms, over the {}ms limit",
+                board.get_generation(),
+                took.as_millis(),
+                limit.as_millis()
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Runs a copy of `board` for `generations` generations and writes every one of them, starting
+/// with the current one, to a single file as RLE patterns one after another.
+///
+/// Each frame starts with a `#N generation <n>` comment and ends with the usual `!`, so the
+/// file can be read back with `Configuration::load_sequence`.
+pub fn record_rle(board: &board::Board, generations: usize, path: &Path) -> io::Result<()> {
+    let mut board = board.clone();
+    let mut file = io::BufWriter::new(fs::File::create(path)?);
+    for n in 0..=generations {
+        if n > 0 {
+            board.update();
+        }
+        let frame = board
+            .extract_region(0, 0, board.rows, board.cols)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        write!(file, "#N generation {}\n{}", board.get_generation(), frame.to_rle_string())?;
+    }
+    file.flush()
+}
+
+/// Runs `conf` under `rule_a` and `rule_b` side by side for `generations` generations and
+/// returns how many cells differ between the two after each one, starting with generation 0
+///
+/// Both boards grow so nothing reaches the edge, so the only differences come from the
+/// rules. The configuration's own rule is ignored.
+pub fn compare_rules(conf: &config::Configuration, rule_a: Rule, rule_b: Rule, generations: usize) -> Vec<usize> {
+    let mut a = board::Board::from_config(conf, 1);
+    let mut b = a.clone();
+    a.rule = rule_a;
+    b.rule = rule_b;
+
+    // the boards can grow differently, so cells are compared by where they are from the origin
+    let alive = |board: &board::Board| -> HashSet<(isize, isize)> {
+        board.iter_cells_from_origin().filter(|(_, alive)| *alive).map(|(cell, _)| cell).collect()
+    };
+    let mut divergence = Vec::with_capacity(generations + 1);
+    for n in 0..=generations {
+        if n > 0 {
+            for board in [&mut a, &mut b].iter_mut() {
+                board.expand_to_margin(1);
+                board.update();
+            }
         }
+        divergence.push(alive(&a).symmetric_difference(&alive(&b)).count());
+    }
+    divergence
+}
+
+/// How a pattern did in a headless run, as measured by `evaluate`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Fitness {
+    /// Generations until the pattern died or settled into a cycle, or the whole run if it didn't
+    pub longevity: usize,
+    /// true if the pattern died or settled into a cycle within the run
+    pub settled: bool,
+    /// Most cells alive at once
+    pub peak_population: usize,
+    /// Cells alive at the end of `longevity`
+    pub final_population: usize,
+    /// Total cells born over the first `longevity` generations
+    pub activity: usize,
+}
+
+/// Runs `conf` headless for up to `max_gen` generations and measures how it did, for scoring
+/// patterns in a soup or evolutionary search.
+///
+/// The board grows so nothing ever reaches the edge. A pattern that settles into a cycle, still
+/// lifes and oscillators included, is measured up to the first generation of that cycle.
+pub fn evaluate(conf: &config::Configuration, max_gen: usize) -> Fitness {
+    let mut board = board::Board::from_config(conf, 1);
+    board.rule = conf.rule().unwrap_or_default();
 
-        if board.get_num_alive_cells() == 0 {
+    // generation each board state was first seen, with the population and births so far
+    let mut seen = CycleDetector::new();
+    let mut populations = vec![board.get_num_alive_cells()];
+    let mut births = vec![0];
+    let mut settled_at = None;
+    for gen in 0..=max_gen {
+        if let Some(first) = seen.observe(&board) {
+            settled_at = Some(first);
             break;
         }
+        if gen < max_gen {
+            board.expand_to_margin(1);
+            let tick = board.tick();
+            populations.push(board.get_num_alive_cells());
+            births.push(births[gen] + tick.births);
+        }
+    }
+
+    let longevity = settled_at.unwrap_or(max_gen);
+    Fitness {
+        longevity,
+        settled: settled_at.is_some(),
+        peak_population: populations[..=longevity].iter().copied().max().unwrap_or(0),
+        final_population: populations[longevity],
+        activity: births[longevity],
+    }
+}
+
+/// Loads every pattern file in `dir` and runs it headless for `generations` generations.
+///
+/// Returns the result for each file that was tried, sorted by path. Files that can't be read
+/// or parsed are reported as failures.
+pub fn test_patterns(dir: &Path, generations: usize) -> io::Result<Vec<(PathBuf, Result<(), String>)>> {
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|p| match p.extension().and_then(|e| e.to_str()) {
+            Some(ext) => ext == "rle" || ext == "cells" || ext == "json",
+            None => false,
+        })
+        .collect();
+    paths.sort();
+
+    let results = paths
+        .into_iter()
+        .map(|p| {
+            let result = run_pattern(&p, generations);
+            (p, result)
+        })
+        .collect();
+    Ok(results)
+}
+
+/// Runs `test_patterns` and prints a pass/fail line per file. Returns true if all passed.
+pub fn report_test_patterns(dir: &Path, generations: usize) -> bool {
+    let results = match test_patterns(dir, generations) {
+        Ok(r) => r,
+        Err(why) => {
+            eprintln!("couldn't read {}: {}", dir.display(), why);
+            return false;
+        }
+    };
+
+    let mut all_passed = true;
+    for (path, result) in &results {
+        match result {
+            Ok(()) => println!("PASS {}", path.display()),
+            Err(why) => {
+                all_passed = false;
+                println!("FAIL {}: {}", path.display(), why);
+            }
+        }
+    }
+    all_passed
+}
+
+/// Size of the charts drawn by `plot_population`, and the space left around the plot for the axes
+#[cfg(feature = "image")]
+const PLOT_SIZE: (u32, u32) = (640, 480);
+#[cfg(feature = "image")]
+const PLOT_MARGIN: u32 = 20;
+
+/// Draws a line chart of the population in each generation of `history` to the PNG at `path`
+///
+/// Generations run left to right and the tallest point of the line is the largest population.
+/// There are no labels, just the axes and the line.
+#[cfg(feature = "image")]
+pub fn plot_population(history: &[usize], path: &Path) -> image::ImageResult<()> {
+    let (width, height) = PLOT_SIZE;
+    let mut img = image::RgbImage::from_pixel(width, height, image::Rgb([255, 255, 255]));
+
+    // axes along the left and bottom
+    let axis = image::Rgb([128, 128, 128]);
+    let bottom = height - PLOT_MARGIN;
+    for x in PLOT_MARGIN..width - PLOT_MARGIN {
+        img.put_pixel(x, bottom, axis);
+    }
+    for y in PLOT_MARGIN..=bottom {
+        img.put_pixel(PLOT_MARGIN, y, axis);
+    }
+
+    let max = history.iter().copied().max().unwrap_or(0).max(1) as f64;
+    let steps = (history.len().max(2) - 1) as f64;
+    let plot_width = f64::from(width - 2 * PLOT_MARGIN - 1);
+    let plot_height = f64::from(height - 2 * PLOT_MARGIN);
+    let points: Vec<(f64, f64)> = history
+        .iter()
+        .enumerate()
+        .map(|(n, pop)| {
+            let x = f64::from(PLOT_MARGIN) + n as f64 / steps * plot_width;
+            let y = f64::from(bottom) - *pop as f64 / max * plot_height;
+            (x, y)
+        })
+        .collect();
+
+    let line = image::Rgb([0, 0, 200]);
+    if let [only] = points.as_slice() {
+        draw_line(&mut img, *only, *only, line);
+    }
+    for pair in points.windows(2) {
+        draw_line(&mut img, pair[0], pair[1], line);
+    }
+    img.save(path)
+}
+
+/// Colors every pixel along the straight line from `from` to `to`, both (x, y)
+#[cfg(feature = "image")]
+fn draw_line(img: &mut image::RgbImage, from: (f64, f64), to: (f64, f64), color: image::Rgb<u8>) {
+    let steps = (to.0 - from.0).abs().max((to.1 - from.1).abs()).ceil().max(1.) as usize;
+    for i in 0..=steps {
+        let t = i as f64 / steps as f64;
+        let x = (from.0 + (to.0 - from.0) * t).round() as u32;
+        let y = (from.1 + (to.1 - from.1) * t).round() as u32;
+        if x < img.width() && y < img.height() {
+            img.put_pixel(x, y, color);
+        }
+    }
+}
+
+/// A list of patterns to stamp onto a board, as read by `load_scene`
+#[derive(Deserialize)]
+struct Scene {
+    patterns: Vec<SceneEntry>,
+}
+
+#[derive(Deserialize)]
+struct SceneEntry {
+    /// Pattern file, relative to the manifest
+    path: PathBuf,
+    /// Where the top left corner of the pattern goes, after it's oriented
+    #[serde(default)]
+    row: usize,
+    #[serde(default)]
+    col: usize,
+    /// One of the names `board::Orientation` parses, like `rotate90`. Placed as given if missing
+    orientation: Option<String>,
+}
+
+/// Builds a scene by stamping every pattern listed in the JSON manifest at `manifest` onto `board`
+///
+/// The manifest looks like `{"patterns": [{"path": "glider.rle", "row": 2, "col": 5, "orientation": "rotate90"}]}`,
+/// with paths relative to the manifest. Cells outside the patterns are left alone. Errors
+/// say which entry failed.
+pub fn load_scene(manifest: &Path, board: &mut board::Board) -> Result<(), String> {
+    let s = fs::read_to_string(manifest).map_err(|why| why.to_string())?;
+    let scene: Scene = serde_json::from_str(&s).map_err(|why| why.to_string())?;
+    let dir = manifest.parent().unwrap_or_else(|| Path::new(""));
+
+    for (n, entry) in scene.patterns.iter().enumerate() {
+        stamp_scene_entry(dir, entry, board).map_err(|why| format!("entry {} ({}): {}", n, entry.path.display(), why))?;
+    }
+    Ok(())
+}
+
+fn stamp_scene_entry(dir: &Path, entry: &SceneEntry, board: &mut board::Board) -> Result<(), String> {
+    let path = dir.join(&entry.path);
+    let s = fs::read_to_string(&path).map_err(|why| why.to_string())?;
+    let conf = config::Configuration::parse(&s, config::Format::from_path(&path))?;
+    let orientation = match &entry.orientation {
+        Some(o) => o.parse()?,
+        None => board::Orientation::Identity,
+    };
+    board.stamp_config_oriented(&conf, entry.row, entry.col, orientation).map_err(|e| e.to_string())
+}
+
+/// Loads the pattern at `pattern`, runs it for `generations` generations and checks the result
+/// against the pattern at `expected`, printing the first cell that differs if they don't match.
+///
+/// Only the alive cells are compared, so the expected pattern can come from another program
+/// like Golly that crops its output to the live cells. Returns true if they match.
+pub fn verify(pattern: &Path, generations: usize, expected: &Path) -> bool {
+    match check_generation(pattern, generations, expected) {
+        Ok(()) => true,
+        Err(why) => {
+            eprintln!("{} doesn't match {} after {} generations: {}", pattern.display(), expected.display(), generations, why);
+            false
+        }
+    }
+}
+
+fn check_generation(pattern: &Path, generations: usize, expected: &Path) -> Result<(), String> {
+    let conf = config::Configuration::load(pattern)?;
+    // the board grows so nothing ever reaches the edges
+    let mut board = board::Board::from_config(&conf, 1);
+    board.rule = conf.rule()?;
+    for _ in 0..generations {
+        board.expand_to_margin(1);
+        board.update();
+    }
+
+    let actual = crop(&board)?;
+    let expected = crop(&board::Board::from_config(&config::Configuration::load(expected)?, 0))?;
 
-        sleep(update_rate);
+    // line both up on a board big enough for either, from their top left alive cells
+    let rows = actual.rows.max(expected.rows);
+    let cols = actual.cols.max(expected.cols);
+    let place = |conf: &config::Configuration| -> Result<board::Board, String> {
+        let mut b = board::Board::new(rows, cols);
+        conf.apply_config_at(&mut b, 0, 0).map_err(|e| e.to_string())?;
+        Ok(b)
+    };
+    let (actual, expected) = (place(&actual)?, place(&expected)?);
+
+    let first = actual.iter_changes(&expected).find(|(_, kind)| *kind != board::ChangeKind::Unchanged);
+    match first {
+        Some(((r, c), board::ChangeKind::Born)) => Err(format!("cell ({}, {}) is alive but should be dead", r, c)),
+        Some(((r, c), _)) => Err(format!("cell ({}, {}) is dead but should be alive", r, c)),
+        None => Ok(()),
+    }
+}
+
+/// returns the smallest region of the board holding all of its alive cells
+fn crop(board: &board::Board) -> Result<config::Configuration, String> {
+    let alive: Vec<(usize, usize)> = board.iter_cells().filter(|(_, a)| **a).map(|(rc, _)| rc).collect();
+    let r0 = alive.iter().map(|(r, _)| *r).min().unwrap_or(0);
+    let c0 = alive.iter().map(|(_, c)| *c).min().unwrap_or(0);
+    let r1 = alive.iter().map(|(r, _)| *r + 1).max().unwrap_or(0);
+    let c1 = alive.iter().map(|(_, c)| *c + 1).max().unwrap_or(0);
+    board.extract_region(r0, c0, r1, c1).map_err(String::from)
+}
+
+fn run_pattern(path: &Path, generations: usize) -> Result<(), String> {
+    let conf = config::Configuration::load(path)?;
+    let mut board = board::Board::from_config(&conf, 0);
+    board.rule = conf.rule()?;
+
+    for _ in 0..generations {
         board.update();
     }
-}
\ No newline at end of file
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn size_precedence() {
+        let glider = config::Configuration::builtin("glider").unwrap();
+        let big = config::Configuration::builtin("glider-gun").unwrap();
+
+        assert_eq!(resolve_size(40, 80, SizeSource::Default, None), Ok((40, 80, SizeSource::Default)));
+        assert_eq!(resolve_size(20, 30, SizeSource::Fit, Some(&glider)), Ok((20, 30, SizeSource::Fit)));
+        assert_eq!(resolve_size(5, 5, SizeSource::Flag, Some(&glider)), Ok((5, 5, SizeSource::Flag)));
+
+        // the pattern beats the terminal and the default, but only grows what it needs to
+        assert_eq!(
+            resolve_size(40, 20, SizeSource::Fit, Some(&big)),
+            Ok((40, big.cols, SizeSource::Config))
+        );
+        assert_eq!(
+            resolve_size(2, 2, SizeSource::Default, Some(&glider)),
+            Ok((3, 3, SizeSource::Config))
+        );
+
+        // and an explicit size beats the pattern
+        assert!(resolve_size(2, 2, SizeSource::Flag, Some(&glider)).is_err());
+    }
+
+    #[test]
+    fn viewport_follows_terminal() {
+        let mut board = board::Board::new(100, 200);
+        board.grid[50][100].is_alive = true;
+
+        let mut view = Viewport::fit(&board, (42, 23));
+        assert!(view.needed);
+        assert_eq!((view.height, view.width), (20, 40));
+        view.pan(&board, -30, 50);
+        let center = (view.top + view.height / 2, view.left + view.width / 2);
+
+        // the same part of the board stays in the middle as the terminal grows and shrinks
+        view.refit(&board, (82, 43));
+        assert_eq!((view.height, view.width), (40, 80));
+        assert_eq!((view.top + view.height / 2, view.left + view.width / 2), center);
+        view.refit(&board, (12, 8));
+        assert_eq!((view.height, view.width), (5, 10));
+        assert_eq!((view.top + view.height / 2, view.left + view.width / 2), center);
+
+        // until the whole board fits and the viewport isn't needed any more
+        view.refit(&board, (300, 200));
+        assert!(!view.needed);
+        assert_eq!((view.top, view.left, view.height, view.width), (0, 0, 100, 200));
+    }
+
+    #[test]
+    fn json_frames() {
+        let mut board = board::Board::new(3, 3);
+        let mut out = Vec::new();
+        write_json_frame(&board, &mut out).unwrap();
+        board.stamp_rle("o$2bo!", 0, 0).unwrap();
+        board.generation = 4;
+        write_json_frame(&board, &mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "{\"generation\":0,\"alive\":[]}\n{\"generation\":4,\"alive\":[[0,0],[1,2]]}\n"
+        );
+    }
+
+    #[test]
+    fn headless_progress() {
+        let mut board = board::Board::from_config(&config::Configuration::builtin("glider").unwrap(), 10);
+        let mut reports = vec![];
+        run_headless(&mut board, 10, 4, None, Some(Box::new(|gen, alive| reports.push((gen, alive))))).unwrap();
+        assert_eq!(reports, vec![(4, 5), (8, 5), (10, 5)]);
+        assert_eq!(board.get_generation(), 10);
+
+        let mut reports = vec![];
+        run_headless(&mut board, 3, 0, None, Some(Box::new(|gen, alive| reports.push((gen, alive))))).unwrap();
+        assert_eq!(reports, vec![(13, 5)]);
+
+        run_headless(&mut board, 2, 1, None, None).unwrap();
+        assert_eq!(board.get_generation(), 15);
+
+        // stops after the first generation that's too slow, a generous limit never trips
+        let mut big = board::Board::new(200, 200);
+        big.initialize_random_symmetric(0.3, board::Symmetry::Both, 5);
+        let why = run_headless(&mut big, 10, 0, Some(Duration::ZERO), None).unwrap_err();
+        assert!(why.starts_with("generation 1 took"));
+        assert_eq!(big.get_generation(), 1);
+        run_headless(&mut big, 2, 0, Some(Duration::from_secs(600)), None).unwrap();
+    }
+
+    #[test]
+    fn rule_divergence() {
+        let conway = Rule::conway();
+        let highlife: Rule = "B36/S23".parse().unwrap();
+
+        // the same rule never diverges
+        let r_pentomino = config::Configuration::builtin("r-pentomino").unwrap();
+        assert_eq!(compare_rules(&r_pentomino, conway, conway, 5), vec![0; 6]);
+
+        // a blinker never has six neighbors, so HighLife plays it the same
+        let blinker = config::Configuration::builtin("blinker").unwrap();
+        assert_eq!(compare_rules(&blinker, conway, highlife, 4), vec![0; 5]);
+
+        // but a block dies out under a rule without survival
+        let block = config::Configuration::from_cells_str("OO\nOO").unwrap();
+        let no_survival: Rule = "B3/S".parse().unwrap();
+        assert_eq!(compare_rules(&block, conway, no_survival, 2), vec![0, 4, 4]);
+    }
+
+    #[test]
+    fn fitness() {
+        let cells = |s: &str| config::Configuration::from_cells_str(s).unwrap();
+
+        let block = evaluate(&cells("OO\nOO"), 10);
+        assert_eq!(block, Fitness { longevity: 0, settled: true, peak_population: 4, final_population: 4, activity: 0 });
+
+        let pair = evaluate(&cells("OO"), 10);
+        assert_eq!(pair, Fitness { longevity: 1, settled: true, peak_population: 2, final_population: 0, activity: 0 });
+
+        // three cells of a block fill in the fourth and then sit still
+        let pre_block = evaluate(&cells("OO\nO."), 10);
+        assert_eq!(pre_block, Fitness { longevity: 1, settled: true, peak_population: 4, final_population: 4, activity: 1 });
+
+        let blinker = evaluate(&config::Configuration::builtin("blinker").unwrap(), 10);
+        assert_eq!((blinker.longevity, blinker.settled, blinker.final_population), (0, true, 3));
+
+        // a glider never repeats exactly, it just moves
+        let glider = evaluate(&config::Configuration::builtin("glider").unwrap(), 12);
+        assert_eq!((glider.longevity, glider.settled), (12, false));
+        assert_eq!((glider.peak_population, glider.final_population), (5, 5));
+        assert!(glider.activity >= 12);
+    }
+
+    #[test]
+    fn verify_generations() {
+        let dir = std::env::temp_dir();
+        let write = |name: &str, contents: &str| {
+            let path = dir.join(format!("gameoflife-verify-{}-{}", std::process::id(), name));
+            fs::write(&path, contents).unwrap();
+            path
+        };
+        let glider = write("glider.cells", ".O\n..O\nOOO");
+        // four generations later it's the same shape, one cell down and to the right,
+        // and the expected file doesn't have to keep that offset
+        let moved = write("moved.cells", "....\n..O\n...O\n.OOO");
+        let flipped = write("flipped.cells", ".O\nO\nOOO");
+        let empty = write("empty.rle", "x = 1, y = 1\nb!");
+
+        assert_eq!(check_generation(&glider, 4, &moved), Ok(()));
+        assert!(verify(&glider, 4, &glider));
+        assert_eq!(check_generation(&glider, 0, &flipped), Err(String::from("cell (1, 0) is dead but should be alive")));
+        assert_eq!(check_generation(&glider, 1, &glider), Err(String::from("cell (0, 0) is alive but should be dead")));
+        assert!(!verify(&glider, 0, &empty));
+
+        for path in &[glider, moved, flipped, empty] {
+            fs::remove_file(path).unwrap();
+        }
+    }
+
+    #[test]
+    fn pattern_failures() {
+        let dir = std::env::temp_dir().join(format!("gameoflife-test-patterns-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("a-glider.cells"), ".O\n..O\nOOO").unwrap();
+        fs::write(dir.join("b-garbled.rle"), "x = 3, y = 1, rule = B9/S23\n3o!").unwrap();
+        fs::write(dir.join("c-binary.cells"), [0xff, 0xfe, 0x00]).unwrap();
+
+        let results = test_patterns(&dir, 4).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].1, Ok(()));
+        assert!(results[1].1.is_err());
+        assert!(results[2].1.as_ref().unwrap_err().starts_with("couldn't read"), "{:?}", results[2]);
+    }
+
+    #[test]
+    fn scenes() {
+        let dir = std::env::temp_dir().join(format!("gameoflife-scene-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("glider.cells"), ".O\n..O\nOOO").unwrap();
+        fs::write(dir.join("block.rle"), "x = 2, y = 2\n2o$2o!").unwrap();
+        let manifest = dir.join("scene.json");
+
+        fs::write(&manifest, r#"{"patterns": [
+            {"path": "glider.cells", "orientation": "rotate180"},
+            {"path": "block.rle", "row": 4, "col": 5}
+        ]}"#).unwrap();
+        let mut b = board::Board::new(8, 8);
+        load_scene(&manifest, &mut b).unwrap();
+        assert_eq!(b.get_num_alive_cells(), 9);
+        assert!(b.grid[0][0].is_alive && b.grid[2][1].is_alive && b.grid[5][6].is_alive);
+        assert!(!b.grid[1][1].is_alive);
+
+        fs::write(&manifest, r#"{"patterns": [
+            {"path": "block.rle"},
+            {"path": "glider.cells", "orientation": "sideways"}
+        ]}"#).unwrap();
+        assert_eq!(
+            load_scene(&manifest, &mut b),
+            Err(String::from("entry 1 (glider.cells): Unknown orientation: sideways"))
+        );
+        fs::write(&manifest, r#"{"patterns": [{"path": "missing.rle"}]}"#).unwrap();
+        assert!(load_scene(&manifest, &mut b).unwrap_err().starts_with("entry 0 (missing.rle)"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "image")]
+    fn population_plot() {
+        let path = std::env::temp_dir().join(format!("gameoflife-plot-{}.png", std::process::id()));
+        plot_population(&[0, 5, 10], &path).unwrap();
+        let img = image::open(&path).unwrap().to_rgb8();
+        fs::remove_file(&path).unwrap();
+
+        let (width, height) = PLOT_SIZE;
+        assert_eq!(img.dimensions(), PLOT_SIZE);
+        let line = image::Rgb([0, 0, 200]);
+        // starts at the bottom left corner and ends at the top right
+        assert_eq!(*img.get_pixel(PLOT_MARGIN, height - PLOT_MARGIN), line);
+        assert_eq!(*img.get_pixel(width - PLOT_MARGIN - 1, PLOT_MARGIN), line);
+        assert_eq!(*img.get_pixel(width / 2, height / 2), line);
+        assert_eq!(*img.get_pixel(width / 2, PLOT_MARGIN), image::Rgb([255, 255, 255]));
+
+        plot_population(&[], &path).unwrap();
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "tokio")]
+    fn run_async_steps() {
+        let mut b = board::Board::new(5, 5);
+        config::Configuration::builtin("blinker").unwrap().apply_config_at(&mut b, 2, 1).unwrap();
+
+        let runtime = tokio::runtime::Builder::new_current_thread().enable_time().build().unwrap();
+        let mut seen = Vec::new();
+        let b = runtime.block_on(run_async(b, Duration::from_millis(1), 3, |b| seen.push(b.get_generation())));
+
+        assert_eq!(seen, vec![1, 2, 3]);
+        assert_eq!(b.get_num_alive_cells(), 3);
+        assert!(b.grid[1][2].is_alive);
+    }
+}
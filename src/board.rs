@@ -1,15 +1,355 @@
 /// The board on which Game of Life is played
 ///
+use std::collections::HashMap;
+use std::convert::TryFrom;
 use std::fmt::{Display, Formatter, Error};
+use std::io::{self, Read, Write};
+use std::str::FromStr;
 use termion;
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
 
 use crate::cell::Cell;
+use crate::config::{ApplyError, Configuration, DEFAULT_MAX_DENSE_CELLS};
+use crate::cycle::CycleDetector;
+use crate::objects;
+use crate::rule::Rule;
+use crate::topology::{EdgeMode, Topology};
 
+/// Spatial distributions for seeding a board with random alive cells
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum InitDistribution {
+    /// Every cell is alive with the same probability
+    Uniform(f32),
+    /// Cells are alive with probability `center_density` in the middle of the board,
+    /// falling off as a gaussian with standard deviation `sigma` cells
+    Gaussian { center_density: f32, sigma: f32 },
+    /// Cells on the "black" squares of a checkerboard are alive with probability
+    /// `on_density`, the rest with `off_density`
+    Checkerboard { on_density: f32, off_density: f32 },
+}
+
+impl InitDistribution {
+    /// returns the probability that the cell at (`r`, `c`) starts alive
+    fn density(&self, r: usize, c: usize, rows: usize, cols: usize) -> f32 {
+        match *self {
+            InitDistribution::Uniform(p) => p,
+            InitDistribution::Gaussian { center_density, sigma } => {
+                let dy = r as f32 - (rows as f32 - 1.) / 2.;
+                let dx = c as f32 - (cols as f32 - 1.) / 2.;
+                center_density * (-(dx * dx + dy * dy) / (2. * sigma * sigma)).exp()
+            }
+            InitDistribution::Checkerboard { on_density, off_density } => {
+                if (r + c) % 2 == 0 { on_density } else { off_density }
+            }
+        }
+    }
+}
+
+/// Mirror symmetries a randomly seeded board can be given
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Symmetry {
+    /// The right half is a mirror image of the left half
+    Horizontal,
+    /// The bottom half is a mirror image of the top half
+    Vertical,
+    /// Mirrored both left to right and top to bottom, so one quadrant sets the whole board
+    Both,
+    /// The board looks the same turned upside down (180 degree rotation)
+    Rotational,
+}
+
+impl Symmetry {
+    /// returns the cell that (`r`, `c`) copies its state from. It is never after (`r`, `c`)
+    /// in row major order, and is (`r`, `c`) itself for cells that are seeded randomly
+    fn source(&self, r: usize, c: usize, rows: usize, cols: usize) -> (usize, usize) {
+        let (mr, mc) = (rows - 1 - r, cols - 1 - c);
+        match *self {
+            Symmetry::Horizontal => (r, c.min(mc)),
+            Symmetry::Vertical => (r.min(mr), c),
+            Symmetry::Both => (r.min(mr), c.min(mc)),
+            Symmetry::Rotational => (r, c).min((mr, mc)),
+        }
+    }
+}
+
+/// The 8 ways a pattern can be rotated or reflected when it's placed on a board
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Orientation {
+    /// As given
+    Identity,
+    /// Turned a quarter clockwise
+    Rotate90,
+    /// Turned upside down
+    Rotate180,
+    /// Turned a quarter counterclockwise
+    Rotate270,
+    /// Mirrored left to right
+    FlipHorizontal,
+    /// Mirrored top to bottom
+    FlipVertical,
+    /// Mirrored across the top left to bottom right diagonal
+    Transpose,
+    /// Mirrored across the top right to bottom left diagonal
+    AntiTranspose,
+}
+
+impl Orientation {
+    /// true if the pattern's rows and columns trade places
+    pub(crate) fn swaps_dimensions(&self) -> bool {
+        matches!(
+            *self,
+            Orientation::Rotate90 | Orientation::Rotate270 | Orientation::Transpose | Orientation::AntiTranspose
+        )
+    }
+
+    /// returns the cell of a `rows` by `cols` pattern that ends up at (`r`, `c`) once it's oriented
+    pub(crate) fn source(&self, r: usize, c: usize, rows: usize, cols: usize) -> (usize, usize) {
+        match *self {
+            Orientation::Identity => (r, c),
+            Orientation::Rotate90 => (rows - 1 - c, r),
+            Orientation::Rotate180 => (rows - 1 - r, cols - 1 - c),
+            Orientation::Rotate270 => (c, cols - 1 - r),
+            Orientation::FlipHorizontal => (r, cols - 1 - c),
+            Orientation::FlipVertical => (rows - 1 - r, c),
+            Orientation::Transpose => (c, r),
+            Orientation::AntiTranspose => (rows - 1 - c, cols - 1 - r),
+        }
+    }
+}
+
+impl FromStr for Orientation {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Orientation, String> {
+        match s.to_ascii_lowercase().replace('_', "-").as_str() {
+            "identity" | "none" => Ok(Orientation::Identity),
+            "rotate90" | "cw" => Ok(Orientation::Rotate90),
+            "rotate180" => Ok(Orientation::Rotate180),
+            "rotate270" | "ccw" => Ok(Orientation::Rotate270),
+            "flip-horizontal" => Ok(Orientation::FlipHorizontal),
+            "flip-vertical" => Ok(Orientation::FlipVertical),
+            "transpose" => Ok(Orientation::Transpose),
+            "anti-transpose" => Ok(Orientation::AntiTranspose),
+            _ => Err(format!("Unknown orientation: {}", s)),
+        }
+    }
+}
+
+/// Which alive cells count as touching when grouping them into components
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Connectivity {
+    /// Only cells directly above, below, left or right of each other touch
+    Four,
+    /// Every neighbor touches, including diagonals
+    Eight,
+}
+
+/// How `Board::downsample` decides whether a block of cells is alive
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DownsamplePolicy {
+    /// Alive if any cell in the block is
+    Any,
+    /// Alive if more than half the cells in the block are
+    Majority,
+}
+
+/// One of the four edges of a board
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Edge {
+    Top,
+    Bottom,
+    Left,
+    Right,
+}
+
+/// Most cells a board can have for `Board::find_predecessor` to search it
+pub const MAX_PREDECESSOR_CELLS: usize = 64;
+
+/// Most cells `Board::find_predecessor` may have to decide after a cell before it can check
+/// that cell against the target, which the search time grows exponentially with. With dead
+/// edges it's one more than the shorter side of the board, but wrapping edges make the first
+/// cells neighbors of the last ones, so only very small wrapped boards can be searched.
+pub const MAX_PREDECESSOR_WIDTH: usize = 16;
+
+/// State for the backtracking search behind `Board::find_predecessor`. Cells are numbered in
+/// row or column major order and decided one at a time, and each cell is checked against the target as
+/// soon as it and all of its neighbors have been decided.
+struct PredecessorSearch {
+    rule: Rule,
+    target: Vec<bool>,
+    neighbors: Vec<Vec<usize>>,
+    /// `checks[i]` are the cells whose neighborhoods are complete once cell `i` is decided
+    checks: Vec<Vec<usize>>,
+    cells: Vec<bool>,
+    /// Every predecessor found so far, stopping once there are `max` of them
+    found: Vec<Vec<bool>>,
+    max: usize,
+}
+
+impl PredecessorSearch {
+    /// Tries both states for cell `i` and everything after it, returning true once `max` predecessors are found
+    fn search(&mut self, i: usize) -> bool {
+        if i == self.cells.len() {
+            self.found.push(self.cells.clone());
+            return self.found.len() >= self.max;
+        }
+        for &alive in &[false, true] {
+            self.cells[i] = alive;
+            let consistent = self.checks[i].iter().all(|&j| {
+                let count = self.neighbors[j].iter().filter(|&&k| self.cells[k]).count() as u32;
+                self.rule.next_state(self.cells[j], count) == self.target[j]
+            });
+            if consistent && self.search(i + 1) {
+                return true;
+            }
+        }
+        self.cells[i] = false;
+        false
+    }
+}
+
+/// First bytes of every binary snapshot written by `Board::write_binary`
+pub const BINARY_MAGIC: [u8; 4] = *b"GoLB";
+
+/// Version of the binary snapshot format `Board::write_binary` writes and `Board::read_binary` reads
+pub const BINARY_VERSION: u8 = 1;
+
+/// Bytes before the cells in a binary snapshot: the magic, the version and three `u32`s
+const BINARY_HEADER_LEN: usize = 4 + 1 + 3 * 4;
+
+/// Why `Board::read_binary` couldn't read a snapshot
 #[derive(Debug)]
+pub enum BinaryError {
+    Io(io::Error),
+    /// The data doesn't start with `BINARY_MAGIC`, so it isn't a snapshot
+    BadMagic,
+    /// The snapshot was written in a version of the format this crate doesn't know
+    UnsupportedVersion(u8),
+    /// The data ends partway through the header
+    Truncated,
+    /// The header says there are `expected` bytes of cells but there are `actual`
+    SizeMismatch { expected: usize, actual: usize },
+    /// The header gives a size with only one side zero, or more than `DEFAULT_MAX_DENSE_CELLS` cells
+    BadSize { rows: usize, cols: usize },
+}
+
+impl std::fmt::Display for BinaryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            BinaryError::Io(why) => write!(f, "Couldn't read snapshot: {}", why),
+            BinaryError::BadMagic => write!(f, "Not a board snapshot!"),
+            BinaryError::UnsupportedVersion(v) => write!(
+                f,
+                "Snapshot is version {}, only version {} is supported!",
+                v, BINARY_VERSION
+            ),
+            BinaryError::Truncated => write!(f, "Snapshot ends before the end of its header!"),
+            BinaryError::SizeMismatch { expected, actual } => write!(
+                f,
+                "Snapshot should have {} bytes of cells but has {}!",
+                expected, actual
+            ),
+            BinaryError::BadSize { rows, cols } => write!(f, "Snapshot is too big or the wrong shape: {}x{}!", rows, cols),
+        }
+    }
+}
+
+impl std::error::Error for BinaryError {}
+
+impl From<io::Error> for BinaryError {
+    fn from(why: io::Error) -> BinaryError {
+        BinaryError::Io(why)
+    }
+}
+
+/// (row, col) locations of cells on a board
+pub type Locations = Vec<(usize, usize)>;
+
+/// How a single cell changed between two generations
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ChangeKind {
+    Born,
+    Died,
+    Unchanged,
+}
+
+/// 256 color terminal colors for cells with 0 through 8 alive neighbors, from cold to hot
+static HEATMAP_COLORS: [u8; 9] = [16, 17, 19, 26, 37, 100, 178, 202, 196];
+
+/// What happened in a single generation, as returned by `Board::tick`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct TickResult {
+    /// The generation the board is now on
+    pub generation: usize,
+    pub births: usize,
+    pub deaths: usize,
+    /// false if no cell changed, meaning the board is stable
+    pub changed: bool,
+}
+
+#[derive(Clone, Debug)]
 pub struct Board {
     pub rows: usize,
     pub cols: usize,
 
+    /// Draw cells that died last generation as a faint dot when displayed
+    pub ghost: bool,
+
+    /// Color the background of every cell by how many alive neighbors it has when displayed
+    pub heatmap: bool,
+
+    /// Draw every dead cell that has been alive at some point in a dim color when displayed
+    pub trail: bool,
+
+    /// Draw alive cells as the number of alive neighbors they have when displayed, along with
+    /// the counts of dead cells that will be born next generation in green
+    pub show_counts: bool,
+
+    /// Remember every state the board has been in so `distinct_states_visited` can count them.
+    /// Off by default, since it hashes the whole board on every update and keeps every hash
+    pub track_states: bool,
+
+    /// Number of cells from across each wrapped edge to draw faintly outside the border,
+    /// so patterns crossing an edge move smoothly instead of jumping. Only used on square
+    /// boards, and only for edges that aren't dead
+    pub show_wrap_margin: usize,
+
+    /// The rule used to compute the next generation. Defaults to Conway's B3/S23
+    pub rule: Rule,
+
+    /// Which cells count as neighbors. Defaults to the square Moore neighborhood
+    pub topology: Topology,
+
+    /// What cells see past the edges of the board. Defaults to dead cells
+    pub edges: EdgeMode,
+
+    /// Number of times the board has been updated
+    pub(crate) generation: usize,
+
+    /// Number of cells born and died in the last update
+    pub(crate) last_births: usize,
+    pub(crate) last_deaths: usize,
+
+    /// How many cells have died after being alive for each number of generations
+    pub(crate) lifespans: HashMap<u32, usize>,
+
+    /// Which cells were alive in generation 0, saved by the first update
+    pub(crate) initial: Option<Vec<Vec<bool>>>,
+
+    /// Which cells have been alive in any generation before this one
+    pub(crate) visited: Vec<Vec<bool>>,
+
+    /// Whether each cell was alive in each of the last 32 generations, the previous one in the
+    /// lowest bit. Empty unless the rule is a `Rule::History` one
+    pub(crate) history: Vec<Vec<u32>>,
+
+    /// Every state the board has been updated from or to while `track_states` is set
+    pub(crate) states: CycleDetector,
+
+    /// The (row, col) that `get_cell` and `iter_cells_from_origin` call (0, 0)
+    pub(crate) origin: (usize, usize),
+
     /// The grid 2d vector is set up like this:
     ///
     /// ```text
@@ -24,7 +364,10 @@ pub struct Board {
 }
 
 impl Board {
-    /// Initializes a new board of the given dimensions
+    /// Initializes a new board of the given dimensions with all cells dead
+    ///
+    /// Either dimension may be zero. Such a board has no cells, so updating and
+    /// drawing it do nothing.
     ///
     /// # Example
     /// ```
@@ -32,184 +375,2762 @@ impl Board {
     /// let mut b: Board = Board::new(4, 2);
     /// ```
     pub fn new(rows: usize, cols: usize) -> Board {
-        let mut b = Board {
-            grid: Vec::new(),
+        Board {
+            grid: vec![vec![Cell::new(); cols]; rows],
             rows,
             cols,
-        };
+            ghost: false,
+            heatmap: false,
+            trail: false,
+            show_counts: false,
+            track_states: false,
+            show_wrap_margin: 0,
+            rule: Rule::conway(),
+            topology: Topology::Moore,
+            edges: EdgeMode::Dead,
+            generation: 0,
+            last_births: 0,
+            last_deaths: 0,
+            lifespans: HashMap::new(),
+            initial: None,
+            visited: vec![vec![false; cols]; rows],
+            history: Vec::new(),
+            states: CycleDetector::new(),
+            origin: (0, 0),
+        }
+    }
 
+    /// Initializes a new board of the given dimensions with all cells alive
+    pub fn full(rows: usize, cols: usize) -> Board {
+        let mut b = Board::new(rows, cols);
         for r in 0..b.rows {
-            for _ in 0..b.cols {
-            b.grid.push(Vec::new());
-                b.grid[r].push(Cell::new());
+            for c in 0..b.cols {
+                b.grid[r][c].is_alive = true;
             }
         }
         b
     }
 
+    /// Creates the smallest board that fits the configuration with `padding` dead cells on every side
+    ///
+    /// The pattern is stamped in the middle of the board. The configuration's rule
+    /// isn't applied, see `Configuration::rule` for that.
+    pub fn from_config(conf: &Configuration, padding: usize) -> Board {
+        let mut b = Board::new(conf.rows + 2 * padding, conf.cols + 2 * padding);
+        conf.apply_config_at(&mut b, padding, padding).unwrap();
+        b
+    }
+
+    /// Creates a board the size of the image where every pixel brighter than `threshold` is alive
+    #[cfg(feature = "image")]
+    pub fn from_image(img: &image::GrayImage, threshold: u8) -> Board {
+        let mut b = Board::new(img.height() as usize, img.width() as usize);
+        for (x, y, pixel) in img.enumerate_pixels() {
+            b.grid[y as usize][x as usize].is_alive = pixel.0[0] > threshold;
+        }
+        b
+    }
+
+    /// Like `from_image`, but first shrinks the image to fit within `max_rows` by `max_cols`
+    ///
+    /// The aspect ratio is kept. Images that already fit aren't scaled up.
+    #[cfg(feature = "image")]
+    pub fn from_image_scaled(img: &image::GrayImage, threshold: u8, max_rows: usize, max_cols: usize) -> Board {
+        let (width, height) = (img.width() as usize, img.height() as usize);
+        if width <= max_cols && height <= max_rows {
+            return Board::from_image(img, threshold);
+        }
+
+        let scale = (max_cols as f64 / width as f64).min(max_rows as f64 / height as f64);
+        let new_width = ((width as f64 * scale) as u32).max(1);
+        let new_height = ((height as f64 * scale) as u32).max(1);
+        let small = image::imageops::resize(img, new_width, new_height, image::imageops::FilterType::Triangle);
+        Board::from_image(&small, threshold)
+    }
+
+    /// Creates a grayscale image the size of the board, with alive cells white and the rest black
+    ///
+    /// Together with `downsample` this makes thumbnails of big boards.
+    #[cfg(feature = "image")]
+    pub fn to_image(&self) -> image::GrayImage {
+        image::GrayImage::from_fn(self.cols as u32, self.rows as u32, |x, y| {
+            image::Luma([if self.grid[y as usize][x as usize].is_alive { 255 } else { 0 }])
+        })
+    }
+
+    /// Stamps a configuration onto the board so that its cell at `pattern_anchor` lands on
+    /// the board's cell at `anchor`. Both are (row, col). Cells outside the pattern are left alone.
+    pub fn stamp_relative(&mut self, conf: &Configuration, anchor: (usize, usize), pattern_anchor: (usize, usize)) -> Result<(), ApplyError> {
+        let row = anchor.0 as isize - pattern_anchor.0 as isize;
+        let col = anchor.1 as isize - pattern_anchor.1 as isize;
+        if row < 0 || col < 0 {
+            return Err(ApplyError::OffBoard { row, col });
+        }
+        conf.apply_config_at(self, row as usize, col as usize)
+    }
+
+    /// Stamps a configuration onto the board rotated or reflected by `orientation`, with the
+    /// top left corner of the result at (`row`, `col`). Cells outside the pattern are left alone.
+    pub fn stamp_config_oriented(&mut self, conf: &Configuration, row: usize, col: usize, orientation: Orientation) -> Result<(), ApplyError> {
+        conf.oriented(orientation).apply_config_at(self, row, col)
+    }
+
+    /// true if stamping `conf` with its top left corner at (`row`, `col`) would put any of its
+    /// alive cells on top of cells that are already alive
+    pub fn would_overlap(&self, conf: &Configuration, row: usize, col: usize) -> bool {
+        !self.overlaps(conf, row, col).is_empty()
+    }
+
+    /// Like `Configuration::apply_config_at`, but refuses to stamp the configuration if any of
+    /// its alive cells would land on cells that are already alive, listing them in the error
+    pub fn stamp_config_checked(&mut self, conf: &Configuration, row: usize, col: usize) -> Result<(), ApplyError> {
+        conf.check_fits(self, row, col)?;
+        let overlaps = self.overlaps(conf, row, col);
+        if !overlaps.is_empty() {
+            return Err(ApplyError::Overlap(overlaps));
+        }
+        conf.apply_config_at(self, row, col)
+    }
+
+    /// Stamps a pattern written in RLE with its top left corner at (`row`, `col`), leaving the
+    /// rest of the board alone
+    ///
+    /// The header line is optional, so `b.stamp_rle("bo$2bo$3o!", 10, 10)` stamps a glider.
+    pub fn stamp_rle(&mut self, rle: &str, row: usize, col: usize) -> Result<(), String> {
+        let has_header = rle.lines().map(|l| l.trim_start().to_ascii_lowercase()).any(|l| l.starts_with('x') && l.contains('='));
+        let conf = if has_header {
+            Configuration::from_rle_str(rle)?
+        } else {
+            // give it the rest of the board to fill, the dead cells it doesn't use aren't stamped
+            let rows = self.rows.checked_sub(row).filter(|r| *r > 0).ok_or("Pattern would start off the board!")?;
+            let cols = self.cols.checked_sub(col).filter(|c| *c > 0).ok_or("Pattern would start off the board!")?;
+            Configuration::from_rle_str(&format!("x = {}, y = {}\n{}", cols, rows, rle))?
+        };
+        conf.apply_config_at(self, row, col).map_err(|e| e.to_string())
+    }
+
+    /// Stamps `conf` in the first spot, in row major order, where it and `gap` cells all around
+    /// it are dead, so it can't interact with anything already on the board for a while.
+    /// returns the (row, col) its top left corner went to.
+    ///
+    /// The gap stops at the edges of the board, past which everything counts as dead.
+    pub fn stamp_isolated(&mut self, conf: &Configuration, gap: usize) -> Result<(usize, usize), &'static str> {
+        let height = conf.as_grid().len();
+        let width = conf.as_grid().iter().map(|r| r.len()).max().unwrap_or(0);
+        if height > self.rows || width > self.cols {
+            return Err("Pattern is bigger than the board!");
+        }
+
+        // alive cells above and left of each spot, so any box can be counted in constant time
+        let stride = self.cols + 1;
+        let mut sums = vec![0; (self.rows + 1) * stride];
+        for r in 0..self.rows {
+            for c in 0..self.cols {
+                sums[(r + 1) * stride + c + 1] = self.grid[r][c].is_alive as usize + sums[r * stride + c + 1]
+                    + sums[(r + 1) * stride + c]
+                    - sums[r * stride + c];
+            }
+        }
+        let alive_in = |r0: usize, c0: usize, r1: usize, c1: usize| {
+            sums[r1 * stride + c1] + sums[r0 * stride + c0] - sums[r0 * stride + c1] - sums[r1 * stride + c0]
+        };
+
+        for r in 0..=self.rows - height {
+            for c in 0..=self.cols - width {
+                let (r0, c0) = (r.saturating_sub(gap), c.saturating_sub(gap));
+                let r1 = (r + height + gap).min(self.rows);
+                let c1 = (c + width + gap).min(self.cols);
+                if alive_in(r0, c0, r1, c1) == 0 {
+                    conf.apply_config_at(self, r, c).expect("pattern was checked to fit");
+                    return Ok((r, c));
+                }
+            }
+        }
+        Err("No room on the board for the pattern!")
+    }
+
+    /// returns the already alive cells that `conf` would stamp alive cells on top of, in row major order
+    fn overlaps(&self, conf: &Configuration, row: usize, col: usize) -> Locations {
+        let mut cells = Vec::new();
+        for (r, line) in conf.as_grid().iter().enumerate() {
+            for (c, val) in line.iter().enumerate() {
+                let alive = self.grid.get(row + r).and_then(|line| line.get(col + c)).is_some_and(|cell| cell.is_alive);
+                if *val != 0 && alive {
+                    cells.push((row + r, col + c));
+                }
+            }
+        }
+        cells
+    }
+
     /// Configures the cells in the board to alive or dead with the given probability
     pub fn initialize_random(&mut self, prob_density: f32) {
         for r in 0..self.rows {
             for c in 0..self.cols {
                 let v = rand::random::<f32>();
-                if v >= (1. - prob_density) {
-                    self.grid[r][c].is_alive = true;
+                self.grid[r][c].is_alive = v >= (1. - prob_density);
+            }
+        }
+        self.history.clear();
+        self.initial = None;
+    }
+
+    /// Configures the cells in the board to alive or dead following the given distribution
+    ///
+    /// The same `seed` always produces the same board.
+    pub fn initialize_with(&mut self, dist: InitDistribution, seed: u64) {
+        let mut rng = StdRng::seed_from_u64(seed);
+        for r in 0..self.rows {
+            for c in 0..self.cols {
+                let v = rng.gen::<f32>();
+                let prob_density = dist.density(r, c, self.rows, self.cols);
+                self.grid[r][c].is_alive = v >= (1. - prob_density);
+            }
+        }
+        self.history.clear();
+        self.initial = None;
+    }
+
+    /// Seeds part of the board randomly with the given probability and mirrors it
+    /// onto the rest so the whole board has the given symmetry
+    ///
+    /// The same `seed` always produces the same board.
+    pub fn initialize_random_symmetric(&mut self, prob_density: f32, symmetry: Symmetry, seed: u64) {
+        let mut rng = StdRng::seed_from_u64(seed);
+        for r in 0..self.rows {
+            for c in 0..self.cols {
+                let (sr, sc) = symmetry.source(r, c, self.rows, self.cols);
+                self.grid[r][c].is_alive = if (sr, sc) == (r, c) {
+                    rng.gen::<f32>() >= (1. - prob_density)
                 } else {
-                    self.grid[r][c].is_alive = false;
-                }
+                    self.grid[sr][sc].is_alive
+                };
             }
         }
+        self.history.clear();
+        self.initial = None;
     }
 
     /// Sets all cells in the board to dead
     pub fn clear(&mut self) {
         for r in 0..self.rows {
             for c in 0..self.cols {
-                self.grid[r][c].is_alive = false;
+                self.grid[r][c] = Cell::new();
+            }
+        }
+        self.lifespans.clear();
+        self.states.clear();
+        self.history.clear();
+        self.initial = None;
+        for row in self.visited.iter_mut() {
+            for v in row.iter_mut() {
+                *v = false;
             }
         }
     }
 
-    /// Updates all cells to their next state based on their neighbors
-    pub fn update(&mut self) {
-        for r in 0..self.rows {
-            for c in 0..self.cols {
+    /// Sets a cell alive if it is alive in either this board or `other`
+    pub fn union(&mut self, other: &Board) -> Result<(), &'static str> {
+        self.combine(other, |a, b| a || b)
+    }
 
-                let mut alive_neighbors = 0;
-                for y in r..=(r+2) {
-                    for x in c..=(c+2) {
-                        let x: isize = (x as isize) - 1;
-                        let y: isize = (y as isize) - 1;
-                        if x < 0 || x >= (self.cols as isize) {
-                            continue;
-                        }
-                        if y < 0 || y >= (self.rows as isize) {
-                            continue;
-                        }
-                        if x == (c as isize) && y == (r as isize) {
-                            continue;
-                        }
+    /// Sets a cell alive only if it is alive in both this board and `other`
+    pub fn intersection(&mut self, other: &Board) -> Result<(), &'static str> {
+        self.combine(other, |a, b| a && b)
+    }
 
-                        if self.grid[y as usize][x as usize].is_alive {
-                            alive_neighbors += 1;
-                        }
-                    }
-                }
-                self.grid[r][c].update(alive_neighbors);
-            }
+    /// Sets a cell alive only if it is alive in this board but not in `other`
+    pub fn difference(&mut self, other: &Board) -> Result<(), &'static str> {
+        self.combine(other, |a, b| a && !b)
+    }
+
+    /// Applies `op` cell by cell against an equally sized board
+    fn combine<F>(&mut self, other: &Board, op: F) -> Result<(), &'static str>
+    where
+        F: Fn(bool, bool) -> bool,
+    {
+        if self.rows != other.rows || self.cols != other.cols {
+            return Err("Boards must be the same size!");
         }
 
-        for c in 0..self.cols {
-            for r in 0..self.rows {
-                self.grid[r][c].latch_state();
+        for r in 0..self.rows {
+            for c in 0..self.cols {
+                let cell = &mut self.grid[r][c];
+                cell.is_alive = op(cell.is_alive, other.grid[r][c].is_alive);
             }
         }
+        Ok(())
     }
 
-    /// returns the number of alive cells on the board.
-    pub fn get_num_alive_cells(&self) -> usize {
-        let mut cnt = 0;
+    /// Advances the board one generation and reports what happened.
+    ///
+    /// This is the preferred way to step the board, rather than calling `update` and then
+    /// querying the births, deaths and generation separately.
+    pub fn tick(&mut self) -> TickResult {
+        self.update();
+        TickResult {
+            generation: self.generation,
+            births: self.last_births,
+            deaths: self.last_deaths,
+            changed: self.last_births + self.last_deaths > 0,
+        }
+    }
+
+    /// Updates all cells to their next state based on their neighbors
+    pub fn update(&mut self) {
+        self.update_with(|_, _| {}, |_, _| {});
+    }
+
+    /// Like `update`, but calls `on_birth(row, col)` for every cell that came alive and
+    /// `on_death(row, col)` for every cell that died, as the new states are latched
+    pub fn update_with<B, D>(&mut self, on_birth: B, on_death: D)
+    where
+        B: FnMut(usize, usize),
+        D: FnMut(usize, usize),
+    {
+        self.sync_history();
         for r in 0..self.rows {
             for c in 0..self.cols {
-                if self.grid[r][c].is_alive {
-                    cnt += 1;
+                let alive_neighbors = self.live_neighbors(r, c);
+                match self.history.get(r) {
+                    Some(history) => {
+                        let next = self.rule.next_cell_state_with_history(self.grid[r][c].state(), alive_neighbors, history[c]);
+                        self.grid[r][c].set_pending(next);
+                    }
+                    None => self.grid[r][c].update(alive_neighbors, &self.rule),
                 }
             }
         }
-        cnt
+        self.latch(on_birth, on_death);
     }
 
-    /// Iterate over all of the cells on the board
-    ///
-    /// This allows you to know the (x, y) location of all cells and
-    /// if they are alive or not.
-    pub fn iter_cells(&self) -> impl Iterator<Item = ((usize, usize), &bool)> {
-        self.grid
-            .iter()
-            .enumerate()
-            .flat_map(|(x, row)| row.iter().enumerate().map(move |(y, column)| ((x, y), &column.is_alive)))
+    /// Starts keeping each cell's history when the rule needs one, and drops it when it doesn't
+    fn sync_history(&mut self) {
+        if self.rule.history_window() == 0 {
+            self.history = Vec::new();
+        } else if self.history.len() != self.rows {
+            self.history = vec![vec![0; self.cols]; self.rows];
+        }
     }
-}
 
-impl Display for Board {
+    /// returns the state the cell at (`r`, `c`) will be in next generation
+    fn next_cell_state(&self, r: usize, c: usize) -> u8 {
+        let (state, alive_neighbors) = (self.grid[r][c].state(), self.live_neighbors(r, c));
+        match self.history.get(r) {
+            Some(history) => self.rule.next_cell_state_with_history(state, alive_neighbors, history[c]),
+            None => self.rule.next_cell_state(state, alive_neighbors),
+        }
+    }
 
-    /// Displays the Game of Life board on a termial.
-    ///
-    /// As an optimization, we don't draw dead cells but skip to alive
-    /// cells and the boarder.
-    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
-        // Clear the screen and reset cursor
-        write!(
-            f,
-            "{}{}",
-            termion::clear::All,
-            termion::cursor::Goto(1, 1),
-        ).unwrap();
+    /// Like `update`, but works out the next generation on `threads` threads, each taking a
+    /// band of rows. The result is exactly the same as `update`'s.
+    #[cfg(feature = "parallel")]
+    pub fn update_parallel(&mut self, threads: usize) {
+        if self.rows > 0 && self.cols > 0 {
+            self.sync_history();
+            let band = self.rows.div_ceil(threads.max(1));
+            let mut next = vec![0u8; self.rows * self.cols];
 
-        // write top row of the border
-        write!(f, "┌").unwrap();
-        for _ in 0..self.cols {
-            write!(f, "─").unwrap();
+            // every thread only reads the current generation, which isn't touched until
+            // they're all done, so rows on the edges of a band see the same thing either way
+            let board = &*self;
+            std::thread::scope(|s| {
+                for (i, chunk) in next.chunks_mut(band * board.cols).enumerate() {
+                    s.spawn(move || {
+                        for (j, state) in chunk.iter_mut().enumerate() {
+                            let (r, c) = (i * band + j / board.cols, j % board.cols);
+                            *state = board.next_cell_state(r, c);
+                        }
+                    });
+                }
+            });
+
+            for (r, row) in self.grid.iter_mut().enumerate() {
+                for (c, cell) in row.iter_mut().enumerate() {
+                    cell.set_pending(next[r * self.cols + c]);
+                }
+            }
         }
-        writeln!(f, "┐").unwrap();
+        self.latch(|_, _| {}, |_, _| {});
+    }
 
-        // write interior borders and cells
-        let mut x;
-        let mut y = 2;
-        for r in 0..self.rows {
-            write!(f, "│").unwrap();
-            x = 2;
-            for c in 0..self.cols {
-                if self.grid[r][c].is_alive {
-                    write!(
-                        f,
-                        "{}{}",
-                        termion::cursor::Goto(x, y),
-                        self.grid[r][c]
-                    ).unwrap();
+    /// Moves every cell to the pending state worked out by an update, keeping track of
+    /// births, deaths and lifespans along the way
+    fn latch<B, D>(&mut self, mut on_birth: B, mut on_death: D)
+    where
+        B: FnMut(usize, usize),
+        D: FnMut(usize, usize),
+    {
+        if self.initial.is_none() {
+            self.initial = Some(self.grid.iter().map(|row| row.iter().map(|cell| cell.is_alive).collect()).collect());
+        }
+        if self.track_states && self.states.is_empty() {
+            self.track_state();
+        }
+
+        self.last_births = 0;
+        self.last_deaths = 0;
+        for c in 0..self.cols {
+            for r in 0..self.rows {
+                let cell = &mut self.grid[r][c];
+                let was_alive = cell.is_alive;
+                let age = cell.age;
+                self.visited[r][c] |= was_alive;
+                if let Some(history) = self.history.get_mut(r) {
+                    history[c] = (history[c] << 1) | was_alive as u32;
+                }
+                cell.latch_state();
+                if cell.is_alive && !was_alive {
+                    self.last_births += 1;
+                    on_birth(r, c);
+                } else if was_alive && !cell.is_alive {
+                    self.last_deaths += 1;
+                    *self.lifespans.entry(age + 1).or_insert(0) += 1;
+                    on_death(r, c);
                 }
-                x += 1;
             }
-            writeln!(f, "{}│", termion::cursor::Goto(x, y)).unwrap();
-            y += 1;
         }
+        self.generation += 1;
+        if self.track_states {
+            self.track_state();
+        }
+    }
 
-        // write bottom row of the border
-        write!(f, "└").unwrap();
-        for _ in 0..self.cols {
-            write!(f, "─").unwrap();
+    /// Records the current state in `states`
+    fn track_state(&mut self) {
+        let mut states = std::mem::take(&mut self.states);
+        states.observe(self);
+        self.states = states;
+    }
+
+    /// returns roughly how many different states the board has been in, counting the current
+    /// one, as a quick sign of how complicated a pattern is
+    ///
+    /// A still life has been in 1, a period `p` oscillator in `p` once it has gone around, and
+    /// a chaotic pattern in many. Only updates made while `track_states` is set are counted,
+    /// see `CycleDetector` for how states are told apart. Reset by `clear`.
+    ///
+    /// returns `None` if `track_states` isn't set, since nothing has been counted.
+    pub fn distinct_states_visited(&self) -> Option<usize> {
+        if !self.track_states {
+            return None;
         }
-        writeln!(f, "┘").unwrap();
+        Some(self.states.len() + !self.states.contains(self) as usize)
+    }
 
-        Ok(())
+    /// returns how many cells have died after being alive for each number of generations
+    ///
+    /// A cell that is born and dies the next generation has a lifespan of 1. Cells that are
+    /// still alive, like those of a still life, aren't counted. Reset by `clear`.
+    pub fn lifespan_histogram(&self) -> HashMap<u32, usize> {
+        self.lifespans.clone()
     }
-}
 
-#[cfg(test)]
-mod test {
-    use super::*;
+    /// returns the rule the board is played with, which prints in `B3/S23` notation
+    pub fn rule(&self) -> &Rule {
+        &self.rule
+    }
 
-    #[test]
-    fn test_iter() {
-        // configure the board with some known state
-        let mut b = Board::new(5, 5);
-        for (y, row) in b.grid.iter_mut().enumerate() {
-            for (x, cell) in row.iter_mut().enumerate() {
-                if x == y {
-                    cell.is_alive = true;
-                } else {
-                    cell.is_alive = false;
-                }
+    /// returns the number of generations the board has been updated for.
+    pub fn get_generation(&self) -> usize {
+        self.generation
+    }
+
+    /// returns the number of alive cells there will be after `n` more generations.
+    ///
+    /// The board itself isn't changed; a copy is advanced instead.
+    pub fn population_after(&self, n: usize) -> usize {
+        let mut b = self.clone();
+        for _ in 0..n {
+            if b.is_empty() {
+                return 0;
             }
+            b.update();
         }
+        b.get_num_alive_cells()
+    }
 
-        // check to make sure that state matches in the iterator
-        for ((x, y), alive) in b.iter_cells() {
-            if x == y {
-                assert_eq!(*alive, true);
-            } else {
-                assert_eq!(*alive, false);
+    /// returns a smaller board where each cell stands for a `factor` by `factor` block of this
+    /// one, alive or dead according to `policy`
+    ///
+    /// Blocks along the bottom and right edges are cut short when the board doesn't divide
+    /// evenly, and only the cells they do have count. The rule, topology and edges are kept.
+    pub fn downsample(&self, factor: usize, policy: DownsamplePolicy) -> Board {
+        let factor = factor.max(1);
+        let mut small = Board::new(self.rows.div_ceil(factor), self.cols.div_ceil(factor));
+        small.rule = self.rule;
+        small.topology = self.topology;
+        small.edges = self.edges;
+
+        for (r, row) in small.grid.iter_mut().enumerate() {
+            for (c, cell) in row.iter_mut().enumerate() {
+                let rows = r * factor..((r + 1) * factor).min(self.rows);
+                let cols = c * factor..((c + 1) * factor).min(self.cols);
+                let size = rows.len() * cols.len();
+                let alive = rows
+                    .flat_map(|br| cols.clone().map(move |bc| (br, bc)))
+                    .filter(|(br, bc)| self.grid[*br][*bc].is_alive)
+                    .count();
+                cell.is_alive = match policy {
+                    DownsamplePolicy::Any => alive > 0,
+                    DownsamplePolicy::Majority => 2 * alive > size,
+                };
             }
         }
+        small
+    }
+
+    /// returns a board that becomes this one after a single update, with the same size, rule,
+    /// topology and edges, or `None` if there isn't one
+    ///
+    /// Every possible board is searched, so this is only allowed on boards of up to
+    /// `MAX_PREDECESSOR_CELLS` cells that are narrow enough for `MAX_PREDECESSOR_WIDTH`, and
+    /// only for two state rules. An 8x8 board with dead edges takes a second or so.
+    pub fn find_predecessor(&self) -> Result<Option<Board>, &'static str> {
+        self.all_predecessors(1).map(|found| found.into_iter().next())
+    }
+
+    /// returns up to `max` different boards that all become this one after a single update,
+    /// showing that Life can't be run backwards. See `find_predecessor` for the limits.
+    pub fn all_predecessors(&self, max: usize) -> Result<Vec<Board>, &'static str> {
+        let n = self.rows * self.cols;
+        if n > MAX_PREDECESSOR_CELLS {
+            return Err("Board is too big to search for a predecessor!");
+        }
+        if self.rule.states() > 2 {
+            return Err("Predecessors can only be found for rules with two states!");
+        }
+        if self.rule.history_window() > 0 {
+            return Err("Predecessors can't be found for rules with a history!");
+        }
+        if max == 0 {
+            return Ok(Vec::new());
+        }
+
+        // cells are decided along the rows or down the columns, whichever means checking each
+        // one sooner after it's decided
+        let index = |by_cols: bool, (r, c): (usize, usize)| if by_cols { c * self.rows + r } else { r * self.cols + c };
+        let last_neighbor = |by_cols: bool, (r, c): (usize, usize)| {
+            self.neighbors(r, c).map(|(pos, _)| index(by_cols, pos)).fold(index(by_cols, (r, c)), usize::max)
+        };
+        let width = |by_cols: bool| {
+            self.iter_cells().map(|(pos, _)| last_neighbor(by_cols, pos) - index(by_cols, pos)).max().unwrap_or(0)
+        };
+        let by_cols = width(true) < width(false);
+        if width(by_cols) > MAX_PREDECESSOR_WIDTH {
+            return Err("Board is too wide to search for a predecessor!");
+        }
+
+        let mut neighbors = vec![Vec::new(); n];
+        let mut checks = vec![Vec::new(); n];
+        let mut target = vec![false; n];
+        for ((r, c), alive) in self.iter_cells() {
+            let i = index(by_cols, (r, c));
+            checks[last_neighbor(by_cols, (r, c))].push(i);
+            neighbors[i] = self.neighbors(r, c).map(|(pos, _)| index(by_cols, pos)).collect();
+            target[i] = *alive;
+        }
+
+        let mut search = PredecessorSearch {
+            rule: self.rule,
+            target,
+            neighbors,
+            checks,
+            cells: vec![false; n],
+            found: Vec::new(),
+            max,
+        };
+        search.search(0);
+
+        let predecessors = search
+            .found
+            .into_iter()
+            .map(|cells| {
+                let mut prev = Board::new(self.rows, self.cols);
+                prev.rule = self.rule;
+                prev.topology = self.topology;
+                prev.edges = self.edges;
+                for r in 0..self.rows {
+                    for c in 0..self.cols {
+                        prev.grid[r][c].is_alive = cells[index(by_cols, (r, c))];
+                    }
+                }
+                prev
+            })
+            .collect();
+        Ok(predecessors)
+    }
+
+    /// true if nothing becomes this board after an update, making it a Garden of Eden that can
+    /// only ever be a starting position. See `find_predecessor` for the limits on board size.
+    pub fn is_garden_of_eden(&self) -> Result<bool, &'static str> {
+        self.find_predecessor().map(|prev| prev.is_none())
+    }
+
+    /// Advances the board until `predicate` holds, for at most `max_gen` generations, and
+    /// returns the generation it first held on. The board is checked before it's advanced,
+    /// so one that already satisfies `predicate` is left alone.
+    ///
+    /// returns `None`, with the board `max_gen` generations further along, if it never held.
+    pub fn run_until<F: Fn(&Board) -> bool>(&mut self, predicate: F, max_gen: usize) -> Option<usize> {
+        for n in 0..=max_gen {
+            if predicate(self) {
+                return Some(self.generation);
+            }
+            if n < max_gen {
+                self.update();
+            }
+        }
+        None
+    }
+
+    /// returns the number of alive neighbors of the cell at (`row`, `col`).
+    ///
+    /// Cells past the edges of the board are handled according to `edges`.
+    pub fn live_neighbors(&self, row: usize, col: usize) -> u32 {
+        self.neighbors(row, col).filter(|(_, alive)| *alive).count() as u32
+    }
+
+    /// Iterate over the neighbors of the cell at (`row`, `col`)
+    ///
+    /// Yields the (row, col) location of each neighbor on the board and if it is
+    /// alive or not. Neighbors past the edges of the board are wrapped around
+    /// according to `edges`, or skipped if the edge is dead.
+    pub fn neighbors(&self, row: usize, col: usize) -> impl Iterator<Item = ((usize, usize), bool)> + '_ {
+        self.topology
+            .neighbor_offsets(row)
+            .iter()
+            .filter_map(move |(dr, dc)| {
+                let (r, c) = self.edges.resolve(row as isize + dr, col as isize + dc, self.rows, self.cols)?;
+                Some(((r, c), self.grid[r][c].is_alive))
+            })
+    }
+
+    /// Names the well known objects (block, beehive, blinker, glider, ...) on the board
+    ///
+    /// Every cluster of touching alive cells is matched against a small catalog in any
+    /// rotation or reflection. Returns each cluster's name, or `"unknown"` if it isn't in
+    /// the catalog, along with the top left corner of its bounding box.
+    pub fn classify_objects(&self) -> Vec<(String, (usize, usize))> {
+        self.components(Connectivity::Eight)
+            .iter()
+            .map(|cells| {
+                let name = objects::identify(cells).unwrap_or("unknown");
+                let top = cells.iter().map(|(r, _)| *r).min().unwrap();
+                let left = cells.iter().map(|(_, c)| *c).min().unwrap();
+                (name.to_string(), (top, left))
+            })
+            .collect()
+    }
+
+    /// returns how many gliders will cross `edge` in the next generation
+    ///
+    /// A glider counts once, on the generation where it's up against the edge and its next
+    /// phase would reach past it. Counting over a run of a gun tells its period and how many
+    /// gliders it puts out. Gliders are the `components` that match one of the glider phases
+    /// `classify_objects` knows, so one touching anything else isn't counted, and which way
+    /// each is flying comes from its phase and orientation under Conway's rule. On dead edges
+    /// a glider breaks up into debris as it leaves, which can get in the way of the next one.
+    pub fn count_escaping_gliders(&self, edge: Edge) -> usize {
+        self.components(Connectivity::Eight)
+            .iter()
+            .filter(|cells| {
+                let (dr, dc) = match objects::glider_step(cells) {
+                    Some(step) => step,
+                    None => return false,
+                };
+                let top = cells.iter().map(|(r, _)| *r).min().unwrap();
+                let left = cells.iter().map(|(_, c)| *c).min().unwrap();
+                match edge {
+                    Edge::Top => top == 0 && dr < 0,
+                    Edge::Bottom => top + 3 == self.rows && dr > 0,
+                    Edge::Left => left == 0 && dc < 0,
+                    Edge::Right => left + 3 == self.cols && dc > 0,
+                }
+            })
+            .count()
+    }
+
+    /// Groups the alive cells into connected components
+    ///
+    /// Each component is a list of the (row, col) locations of its cells, sorted.
+    /// Components are ordered by their first cell, scanning row by row.
+    pub fn components(&self, connectivity: Connectivity) -> Vec<Vec<(usize, usize)>> {
+        let mut seen = vec![vec![false; self.cols]; self.rows];
+        let mut clusters = Vec::new();
+
+        for r in 0..self.rows {
+            for c in 0..self.cols {
+                if seen[r][c] || !self.grid[r][c].is_alive {
+                    continue;
+                }
+
+                // flood fill out from this cell
+                let mut cluster = Vec::new();
+                let mut stack = vec![(r, c)];
+                seen[r][c] = true;
+                while let Some((y, x)) = stack.pop() {
+                    cluster.push((y, x));
+                    for ((ny, nx), alive) in self.neighbors(y, x) {
+                        let touching = match connectivity {
+                            Connectivity::Four => ny == y || nx == x,
+                            Connectivity::Eight => true,
+                        };
+                        if touching && alive && !seen[ny][nx] {
+                            seen[ny][nx] = true;
+                            stack.push((ny, nx));
+                        }
+                    }
+                }
+                cluster.sort_unstable();
+                clusters.push(cluster);
+            }
+        }
+        clusters
+    }
+
+    /// returns the number of cells that were born in the last update.
+    pub fn get_last_births(&self) -> usize {
+        self.last_births
+    }
+
+    /// returns the number of cells that died in the last update.
+    pub fn get_last_deaths(&self) -> usize {
+        self.last_deaths
+    }
+
+    /// returns the number of alive cells on the board.
+    pub fn get_num_alive_cells(&self) -> usize {
+        let mut cnt = 0;
+        for r in 0..self.rows {
+            for c in 0..self.cols {
+                if self.grid[r][c].is_alive {
+                    cnt += 1;
+                }
+            }
+        }
+        cnt
+    }
+
+    /// returns true if there are no alive cells on the board.
+    ///
+    /// Stops looking as soon as it finds an alive cell, so this is quicker than
+    /// checking `get_num_alive_cells() == 0`.
+    pub fn is_empty(&self) -> bool {
+        !self.grid.iter().any(|row| row.iter().any(|cell| cell.is_alive))
+    }
+
+    /// returns the cells that changed since `prev` along with whether they're now alive
+    ///
+    /// Applying the result to `prev` with `apply_delta` reproduces this board. See
+    /// `iter_changes` for what happens if the boards aren't the same size.
+    pub fn delta(&self, prev: &Board) -> Vec<((usize, usize), bool)> {
+        self.iter_changes(prev)
+            .filter(|(_, kind)| *kind != ChangeKind::Unchanged)
+            .map(|(pos, kind)| (pos, kind == ChangeKind::Born))
+            .collect()
+    }
+
+    /// Iterates over the (row, col) of every cell that is alive now or has been in any earlier
+    /// generation since the board was made or last cleared, in row major order
+    pub fn visited_cells(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.iter_cells()
+            .filter(move |((r, c), alive)| **alive || self.visited[*r][*c])
+            .map(|(rc, _)| rc)
+    }
+
+    /// returns the cells that are alive now but weren't in generation 0, and the cells that
+    /// were alive in generation 0 but aren't now, both as (row, col) in row major order
+    ///
+    /// Generation 0 is the board as it was on its first update since it was last cleared or
+    /// seeded at random, so loading a new pattern with `Configuration::apply_config` starts over.
+    pub fn delta_from_initial(&self) -> (Locations, Locations) {
+        let mut born = Vec::new();
+        let mut died = Vec::new();
+        if let Some(initial) = &self.initial {
+            for ((r, c), alive) in self.iter_cells() {
+                match (initial[r][c], *alive) {
+                    (false, true) => born.push((r, c)),
+                    (true, false) => died.push((r, c)),
+                    _ => {}
+                }
+            }
+        }
+        (born, died)
+    }
+
+    /// returns the cells that will be born and the cells that will die in the next update, both
+    /// as (row, col) in row major order, without updating the board
+    ///
+    /// Dying cells of a Generations rule that are already fading aren't counted as dying again.
+    pub fn preview_changes(&self) -> (Locations, Locations) {
+        let mut born = Vec::new();
+        let mut died = Vec::new();
+        for ((r, c), alive) in self.iter_cells() {
+            match (*alive, self.next_cell_state(r, c) == 1) {
+                (false, true) => born.push((r, c)),
+                (true, false) => died.push((r, c)),
+                _ => {}
+            }
+        }
+        (born, died)
+    }
+
+    /// returns the number of cells that are alive in one board but not the other
+    pub fn hamming_distance(&self, other: &Board) -> Result<usize, &'static str> {
+        if self.rows != other.rows || self.cols != other.cols {
+            return Err("Boards must be the same size!");
+        }
+        Ok(self.iter_changes(other).filter(|(_, kind)| *kind != ChangeKind::Unchanged).count())
+    }
+
+    /// Flips the cell at (`row`, `col`) between alive and dead
+    pub fn toggle_cell(&mut self, row: usize, col: usize) -> Result<(), &'static str> {
+        match self.grid.get_mut(row).and_then(|r| r.get_mut(col)) {
+            Some(cell) => {
+                cell.is_alive = !cell.is_alive;
+                cell.dying = 0;
+                Ok(())
+            }
+            None => Err("Cell is outside of the board!"),
+        }
+    }
+
+    /// Sets each of the given (row, col) locations alive or dead, like the output of `delta`
+    ///
+    /// Like `toggle_cell`, every cell the delta sets stops dying and starts its age over.
+    pub fn apply_delta(&mut self, delta: &[((usize, usize), bool)]) -> Result<(), &'static str> {
+        if delta.iter().any(|((r, c), _)| *r >= self.rows || *c >= self.cols) {
+            return Err("Delta doesn't fit on this board!");
+        }
+        for ((r, c), alive) in delta {
+            let cell = &mut self.grid[*r][*c];
+            cell.is_alive = *alive;
+            cell.dying = 0;
+            cell.age = 0;
+        }
+        Ok(())
+    }
+
+    /// returns a checksum of the board's size and alive cells.
+    ///
+    /// Two boards with the same cells alive always have the same checksum, which makes it
+    /// easy to check that a board rebuilt from deltas matches the original. This is 64 bit
+    /// FNV-1a so it's stable across platforms and versions.
+    pub fn checksum(&self) -> u64 {
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+        let mut feed = |v: u64| {
+            for byte in v.to_le_bytes().iter() {
+                hash ^= u64::from(*byte);
+                hash = hash.wrapping_mul(0x0100_0000_01b3);
+            }
+        };
+
+        feed(self.rows as u64);
+        feed(self.cols as u64);
+        for r in 0..self.rows {
+            for c in 0..self.cols {
+                if self.grid[r][c].is_alive {
+                    feed((r * self.cols + c) as u64);
+                }
+            }
+        }
+        hash
+    }
+
+    /// returns the number of alive cells in rows `r0..r1` and columns `c0..c1`. Any part of the
+    /// region off the board is ignored
+    pub fn alive_in_region(&self, r0: usize, c0: usize, r1: usize, c1: usize) -> usize {
+        let (r1, c1) = (r1.min(self.rows), c1.min(self.cols));
+        if r0 >= r1 || c0 >= c1 {
+            return 0;
+        }
+        self.grid[r0..r1].iter().map(|row| row[c0..c1].iter().filter(|cell| cell.is_alive).count()).sum()
+    }
+
+    /// Copies the cells in rows `r0..r1` and columns `c0..c1` into a new configuration,
+    /// carrying over the board's rule
+    ///
+    /// The end row and column are exclusive, so `r0 == r1` or `c0 == c1` gives an empty configuration.
+    pub fn extract_region(&self, r0: usize, c0: usize, r1: usize, c1: usize) -> Result<Configuration, &'static str> {
+        if r0 > r1 || c0 > c1 {
+            return Err("Region's start is after its end!");
+        }
+        if r1 > self.rows || c1 > self.cols {
+            return Err("Region is outside of the board!");
+        }
+
+        let cells = self.grid[r0..r1]
+            .iter()
+            .map(|row| row[c0..c1].iter().map(|cell| cell.is_alive as u8).collect())
+            .collect();
+        Ok(Configuration {
+            rows: r1 - r0,
+            cols: c1 - c0,
+            rule: Some(self.rule().to_string()),
+            origin: None,
+            rule_conflict: None,
+            board: cells,
+        })
+    }
+
+    /// returns the (row, col) offset that `self` has to be moved by to become `other`,
+    /// or `None` if `other`'s alive cells aren't the same shape as ours
+    pub fn equals_translated(&self, other: &Board) -> Option<(isize, isize)> {
+        let alive = |b: &Board| -> Vec<(isize, isize)> {
+            b.iter_cells()
+                .filter(|(_, alive)| **alive)
+                .map(|((r, c), _)| (r as isize, c as isize))
+                .collect()
+        };
+        let ours = alive(self);
+        let theirs = alive(other);
+        if ours.len() != theirs.len() {
+            return None;
+        }
+
+        // both are in row major order, so the first cells line up if anything does
+        let offset = match (ours.first(), theirs.first()) {
+            (Some(a), Some(b)) => (b.0 - a.0, b.1 - a.1),
+            _ => return Some((0, 0)),
+        };
+        if ours.iter().zip(&theirs).all(|(a, b)| (a.0 + offset.0, a.1 + offset.1) == *b) {
+            Some(offset)
+        } else {
+            None
+        }
+    }
+
+    /// returns the average (row, col) of the alive cells, or `None` if the board is dead.
+    pub fn center_of_mass(&self) -> Option<(f64, f64)> {
+        let mut count = 0;
+        let mut rows = 0;
+        let mut cols = 0;
+        for ((r, c), _) in self.iter_cells().filter(|(_, alive)| **alive) {
+            count += 1;
+            rows += r;
+            cols += c;
+        }
+        if count == 0 {
+            None
+        } else {
+            Some((rows as f64 / count as f64, cols as f64 / count as f64))
+        }
+    }
+
+    /// returns the smallest box holding all alive cells as
+    /// `(top row, left col, bottom row, right col)`, or `None` if the board is dead.
+    pub fn live_bounding_box(&self) -> Option<(usize, usize, usize, usize)> {
+        let mut bbox: Option<(usize, usize, usize, usize)> = None;
+        for r in 0..self.rows {
+            for c in 0..self.cols {
+                if self.grid[r][c].is_alive {
+                    bbox = Some(match bbox {
+                        None => (r, c, r, c),
+                        Some((t, l, b, rt)) => (t.min(r), l.min(c), b.max(r), rt.max(c)),
+                    });
+                }
+            }
+        }
+        bbox
+    }
+
+    /// Shrinks the board down to its alive cells plus `margin` dead cells on every side, where
+    /// the board has that much room, and returns the (rows, cols) cut off the top and left
+    ///
+    /// Cells keep their positions relative to each other, and so do the origin, history and
+    /// trail. A dead board shrinks to nothing. On hex boards one more row may be kept at the
+    /// top so rows stay staggered the same way. Edges that wrap join wherever the new edges are.
+    pub fn compact(&mut self, margin: usize) -> (usize, usize) {
+        let (top, left, bottom, right) = match self.live_bounding_box() {
+            Some((t, l, b, r)) => (t.saturating_sub(margin), l.saturating_sub(margin), b + margin, r + margin),
+            None => (self.rows, self.cols, 0, 0),
+        };
+        let top = match self.topology {
+            Topology::Moore => top,
+            Topology::Hex => top - top % 2,
+        };
+        let rows = (bottom + 1).min(self.rows).saturating_sub(top);
+        let cols = (right + 1).min(self.cols).saturating_sub(left);
+
+        fn crop<T: Clone>(grid: &[Vec<T>], top: usize, left: usize, rows: usize, cols: usize) -> Vec<Vec<T>> {
+            grid[top..top + rows].iter().map(|row| row[left..left + cols].to_vec()).collect()
+        }
+        let (top, left) = (top.min(self.rows), left.min(self.cols));
+        self.grid = crop(&self.grid, top, left, rows, cols);
+        self.visited = crop(&self.visited, top, left, rows, cols);
+        if !self.history.is_empty() {
+            self.history = crop(&self.history, top, left, rows, cols);
+        }
+        self.initial = self.initial.as_ref().map(|initial| crop(initial, top, left, rows, cols));
+        self.origin = (self.origin.0.saturating_sub(top), self.origin.1.saturating_sub(left));
+        self.rows = rows;
+        self.cols = cols;
+        (top, left)
+    }
+
+    /// Grows the board until there are at least `margin` dead cells between the alive cells and
+    /// every edge, and returns the (rows, cols) added to the top and left
+    ///
+    /// The opposite of `compact`, for running a pattern on a board with dead edges that's only
+    /// ever as big as it needs to be. Sides that have to grow get half the board's size again
+    /// on top, so calling this before every update only copies the board now and then. Cells
+    /// keep their positions relative to each other, and so do the origin, history and trail.
+    pub fn expand_to_margin(&mut self, margin: usize) -> (usize, usize) {
+        let (t, l, b, r) = match self.live_bounding_box() {
+            Some(bbox) => bbox,
+            None => return (0, 0),
+        };
+        let grow = |room: usize, size: usize| if room < margin { margin - room + size / 2 } else { 0 };
+        let mut top = grow(t, self.rows);
+        if self.topology == Topology::Hex {
+            top += top % 2;
+        }
+        let left = grow(l, self.cols);
+        let bottom = grow(self.rows - 1 - b, self.rows);
+        let right = grow(self.cols - 1 - r, self.cols);
+        if top + left + bottom + right == 0 {
+            return (0, 0);
+        }
+
+        let cols = self.cols + left + right;
+        fn pad<T: Clone>(grid: &mut Vec<Vec<T>>, dead: T, (top, left, bottom): (usize, usize, usize), cols: usize) {
+            for row in grid.iter_mut() {
+                row.splice(0..0, std::iter::repeat(dead.clone()).take(left));
+                row.resize(cols, dead.clone());
+            }
+            grid.splice(0..0, std::iter::repeat(vec![dead.clone(); cols]).take(top));
+            grid.resize(grid.len() + bottom, vec![dead; cols]);
+        }
+        let sides = (top, left, bottom);
+        pad(&mut self.grid, Cell::new(), sides, cols);
+        pad(&mut self.visited, false, sides, cols);
+        if !self.history.is_empty() {
+            pad(&mut self.history, 0, sides, cols);
+        }
+        if let Some(initial) = self.initial.as_mut() {
+            pad(initial, false, sides, cols);
+        }
+        self.origin = (self.origin.0 + top, self.origin.1 + left);
+        self.rows += top + bottom;
+        self.cols = cols;
+        (top, left)
+    }
+
+    /// Moves every cell `dr` rows down and `dc` columns right on a toroidal board, wrapping
+    /// around the edges so nothing is lost
+    ///
+    /// Cells keep their age and history, and the origin, trail and initial generation move
+    /// along with them, so it's the same simulation seen from a different spot. States seen
+    /// by `distinct_states_visited` are still recognised afterwards, but `checksum` changes,
+    /// since it goes by where cells are on the board. Only boards with `EdgeMode::Torus`
+    /// edges can be shifted, since any other edges would change how the pattern evolves. Hex
+    /// boards can only be shifted by an even number of rows, to keep rows staggered the same
+    /// way.
+    pub fn torus_shift(&mut self, dr: isize, dc: isize) -> Result<(), &'static str> {
+        if self.edges != EdgeMode::Torus {
+            return Err("Only toroidal boards can be shifted!");
+        }
+        if self.topology == Topology::Hex && dr % 2 != 0 {
+            return Err("Hex boards can only be shifted by an even number of rows!");
+        }
+        if self.rows == 0 || self.cols == 0 {
+            return Ok(());
+        }
+        let dr = dr.rem_euclid(self.rows as isize) as usize;
+        let dc = dc.rem_euclid(self.cols as isize) as usize;
+
+        fn shift<T>(grid: &mut [Vec<T>], dr: usize, dc: usize) {
+            grid.rotate_right(dr);
+            for row in grid.iter_mut() {
+                row.rotate_right(dc);
+            }
+        }
+        shift(&mut self.grid, dr, dc);
+        shift(&mut self.visited, dr, dc);
+        if !self.history.is_empty() {
+            shift(&mut self.history, dr, dc);
+        }
+        if let Some(initial) = self.initial.as_mut() {
+            shift(initial, dr, dc);
+        }
+        self.origin = ((self.origin.0 + dr) % self.rows, (self.origin.1 + dc) % self.cols);
+        Ok(())
+    }
+
+    /// returns how many terminal columns it takes to draw `cols` cells
+    fn screen_width(&self, cols: usize) -> usize {
+        match self.topology {
+            Topology::Moore => cols,
+            // every cell is two characters wide, plus room for the odd rows' half cell shift
+            Topology::Hex => 2 * cols + 1,
+        }
+    }
+
+    /// returns the terminal column to draw the `c`th visible cell of row `r` at,
+    /// inside of a border starting at column 1
+    pub(crate) fn screen_x(&self, r: usize, c: usize) -> u16 {
+        match self.topology {
+            Topology::Moore => c as u16 + 2,
+            Topology::Hex => 2 * c as u16 + 2 + (r % 2) as u16,
+        }
+    }
+
+    /// returns how many cells of wrap margin `write_to` draws around the border
+    pub(crate) fn wrap_margin(&self) -> usize {
+        match (self.topology, self.edges) {
+            (Topology::Hex, _) | (_, EdgeMode::Dead) => 0,
+            _ => self.show_wrap_margin,
+        }
+    }
+
+    /// Draws the Game of Life board for a termial straight to a writer.
+    ///
+    /// As an optimization, we don't draw dead cells but skip to alive
+    /// cells and the boarder. If `ghost` is set, cells that died last
+    /// generation are drawn as a faint dot. Hex boards are drawn two
+    /// characters per cell with the odd rows staggered. With a wrap
+    /// margin, the whole board is moved down and right to make room for
+    /// the faint cells from across the edges.
+    pub fn write_to<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        let m = self.wrap_margin() as u16;
+
+        // Clear the screen and reset cursor
+        write!(
+            w,
+            "{}{}",
+            termion::clear::All,
+            termion::cursor::Goto(1 + m, 1 + m),
+        )?;
+
+        // write top row of the border
+        write!(w, "┌")?;
+        for _ in 0..self.screen_width(self.cols) {
+            write!(w, "─")?;
+        }
+        writeln!(w, "┐")?;
+
+        // write interior borders and cells
+        // lines start with a goto rather than relying on the newline, which doesn't
+        // return to the first column when the terminal is in raw mode
+        let right_border = self.screen_width(self.cols) as u16 + 2 + m;
+        for (r, y) in (0..self.rows).zip(2 + m..) {
+            write!(w, "{}│", termion::cursor::Goto(1 + m, y))?;
+            for c in 0..self.cols {
+                self.write_cell(w, r, c, self.screen_x(r, c) + m, y)?;
+            }
+            writeln!(w, "{}│", termion::cursor::Goto(right_border, y))?;
+        }
+
+        // write bottom row of the border
+        write!(w, "{}└", termion::cursor::Goto(1 + m, self.rows as u16 + 2 + m))?;
+        for _ in 0..self.screen_width(self.cols) {
+            write!(w, "─")?;
+        }
+        writeln!(w, "┘")?;
+
+        self.write_wrap_margin(w)
+    }
+
+    /// Draws the cell at (`r`, `c`) at terminal column `x` and row `y`. Dead cells are skipped
+    /// unless they're ghosts, part of a heatmap or about to be born while showing counts.
+    fn write_cell<W: Write>(&self, w: &mut W, r: usize, c: usize, x: u16, y: u16) -> io::Result<()> {
+        let cell = &self.grid[r][c];
+        let goto = termion::cursor::Goto(x, y);
+
+        if self.show_counts && cell.state() <= 1 {
+            let count = self.live_neighbors(r, c);
+            if cell.is_alive {
+                return write!(w, "{}{}", goto, count);
+            } else if self.next_cell_state(r, c) == 1 {
+                let color = termion::color::Fg(termion::color::Green);
+                return write!(w, "{}{}{}{}", goto, color, count, termion::style::Reset);
+            }
+        }
+
+        if self.heatmap {
+            let count = self.live_neighbors(r, c) as usize;
+            if cell.glyph().is_some() || count > 0 {
+                let color = termion::color::Bg(termion::color::AnsiValue(HEATMAP_COLORS[count.min(8)]));
+                write!(w, "{}{}{}{}", goto, color, cell, termion::style::Reset)?;
+            }
+        } else if cell.state() > 1 {
+            write!(w, "{}{}{}{}", goto, self.dying_color(cell.state()), cell, termion::style::Reset)?;
+        } else if cell.glyph().is_some() {
+            write!(w, "{}{}", goto, cell)?;
+        } else if self.ghost && cell.just_died() {
+            write!(w, "{}{}·{}", goto, termion::style::Faint, termion::style::Reset)?;
+        } else if self.trail && self.visited[r][c] {
+            let color = termion::color::Fg(termion::color::AnsiValue::grayscale(6));
+            write!(w, "{}{}░{}", goto, color, termion::style::Reset)?;
+        }
+        Ok(())
+    }
+
+    /// returns the color a dying cell in `state` is drawn in, fading from bright to dim gray as it ages
+    fn dying_color(&self, state: u8) -> termion::color::Fg<termion::color::AnsiValue> {
+        // the last dying state is `states - 1`, which gets the dimmest shade
+        let oldest = u16::from(self.rule.states().saturating_sub(3)).max(1);
+        let age = u16::from(state.saturating_sub(2)).min(oldest);
+        let shade = 20 - age * 16 / oldest;
+        termion::color::Fg(termion::color::AnsiValue::grayscale(shade as u8))
+    }
+
+    /// Draws the cells from across the edges outside of the border, as `write_to` lays it out
+    fn write_wrap_margin<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        let m = self.wrap_margin() as isize;
+        let (rows, cols) = (self.rows as isize, self.cols as isize);
+        if m == 0 || rows == 0 || cols == 0 {
+            return Ok(());
+        }
+
+        // cells off the board sit one further out, on the other side of the border
+        let screen = |i: isize, n: isize| -> u16 {
+            if i < 0 {
+                (i + m + 1) as u16
+            } else if i >= n {
+                (i + m + 3) as u16
+            } else {
+                (i + m + 2) as u16
+            }
+        };
+
+        for r in -m..rows + m {
+            for c in -m..cols + m {
+                if (0..rows).contains(&r) && (0..cols).contains(&c) {
+                    continue;
+                }
+                if let Some((wr, wc)) = self.edges.resolve(r, c, self.rows, self.cols) {
+                    if self.grid[wr][wc].is_alive {
+                        write!(
+                            w,
+                            "{}{}●{}",
+                            termion::cursor::Goto(screen(c, cols), screen(r, rows)),
+                            termion::style::Faint,
+                            termion::style::Reset,
+                        )?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Draws the board as plain text with no terminal escape codes, for writing to files or pipes
+    ///
+    /// Alive and dying cells are drawn just like `write_to`, dead cells are spaces.
+    pub fn write_plain<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        let width = self.screen_width(self.cols);
+        let border: String = "─".repeat(width);
+
+        writeln!(w, "┌{}┐", border)?;
+        for r in 0..self.rows {
+            let mut line = vec![' '; width];
+            for c in 0..self.cols {
+                if let Some(glyph) = self.grid[r][c].glyph() {
+                    line[self.screen_x(r, c) as usize - 2] = glyph;
+                }
+            }
+            writeln!(w, "│{}│", line.into_iter().collect::<String>())?;
+        }
+        writeln!(w, "└{}┘", border)?;
+
+        Ok(())
+    }
+
+    /// Writes the board as a compact binary snapshot, readable by `read_binary`
+    ///
+    /// The format is `BINARY_MAGIC`, a `BINARY_VERSION` byte, then the rows, columns and
+    /// generation as little endian `u32`s, then one bit per cell in row major order, starting
+    /// from the lowest bit of each byte and padded with zeros to a whole byte. Only which cells
+    /// are alive is saved, not the rule or dying states.
+    pub fn write_binary<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        let field = |n: usize| {
+            u32::try_from(n).map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "Board is too big for a snapshot!"))
+        };
+        w.write_all(&BINARY_MAGIC)?;
+        w.write_all(&[BINARY_VERSION])?;
+        for n in &[self.rows, self.cols, self.generation] {
+            w.write_all(&field(*n)?.to_le_bytes())?;
+        }
+
+        let mut bits = vec![0u8; (self.rows * self.cols).div_ceil(8)];
+        for (i, (_, alive)) in self.iter_cells().enumerate() {
+            bits[i / 8] |= (*alive as u8) << (i % 8);
+        }
+        w.write_all(&bits)
+    }
+
+    /// Reads a board written by `write_binary`
+    ///
+    /// Everything left in `r` has to be the snapshot, so data that is cut short or has extra
+    /// bytes after the cells is rejected along with the wrong magic or version. So are sizes
+    /// with only one side zero or more than `DEFAULT_MAX_DENSE_CELLS` cells, before anything is
+    /// allocated for them.
+    pub fn read_binary<R: Read>(r: &mut R) -> Result<Board, BinaryError> {
+        let mut data = Vec::new();
+        r.read_to_end(&mut data)?;
+        if data.len() < BINARY_MAGIC.len() + 1 {
+            return Err(if data.starts_with(&BINARY_MAGIC) { BinaryError::Truncated } else { BinaryError::BadMagic });
+        }
+        if data[..4] != BINARY_MAGIC {
+            return Err(BinaryError::BadMagic);
+        }
+        if data[4] != BINARY_VERSION {
+            return Err(BinaryError::UnsupportedVersion(data[4]));
+        }
+        if data.len() < BINARY_HEADER_LEN {
+            return Err(BinaryError::Truncated);
+        }
+
+        let field = |i: usize| u32::from_le_bytes([data[5 + 4 * i], data[6 + 4 * i], data[7 + 4 * i], data[8 + 4 * i]]) as usize;
+        let (rows, cols, generation) = (field(0), field(1), field(2));
+        if (rows == 0) != (cols == 0) || rows.checked_mul(cols).map_or(true, |n| n > DEFAULT_MAX_DENSE_CELLS) {
+            return Err(BinaryError::BadSize { rows, cols });
+        }
+        let bits = &data[BINARY_HEADER_LEN..];
+        let expected = (rows * cols).div_ceil(8);
+        if bits.len() != expected {
+            return Err(BinaryError::SizeMismatch { expected, actual: bits.len() });
+        }
+
+        let mut b = Board::new(rows, cols);
+        for (i, cell) in b.grid.iter_mut().flatten().enumerate() {
+            cell.is_alive = bits[i / 8] & (1 << (i % 8)) != 0;
+        }
+        b.generation = generation;
+        Ok(b)
+    }
+
+    /// Fills an RGBA framebuffer of `fb_width` by `fb_height` pixels with the board, alive
+    /// cells in `fg` and everything else in `bg`
+    ///
+    /// The board is scaled as large as it fits while keeping its cells square, and centered.
+    /// Nothing is allocated, so this can be called every frame of a game loop.
+    pub fn render_to_framebuffer(&self, buf: &mut [u8], fb_width: u32, fb_height: u32, fg: [u8; 4], bg: [u8; 4]) -> Result<(), &'static str> {
+        let (w, h) = (fb_width as usize, fb_height as usize);
+        let len = w.checked_mul(h).and_then(|n| n.checked_mul(4)).ok_or("Framebuffer is too big!")?;
+        if buf.len() < len {
+            return Err("Framebuffer is too small for its dimensions!");
+        }
+
+        // size of the board on the framebuffer, limited by whichever side runs out first
+        let (draw_w, draw_h) = if self.rows == 0 || self.cols == 0 {
+            (0, 0)
+        } else if w * self.rows <= h * self.cols {
+            (w, self.rows * w / self.cols)
+        } else {
+            (self.cols * h / self.rows, h)
+        };
+        let (left, top) = ((w - draw_w) / 2, (h - draw_h) / 2);
+
+        for (i, px) in buf[..len].chunks_exact_mut(4).enumerate() {
+            let (x, y) = (i % w, i / w);
+            let on_board = (left..left + draw_w).contains(&x) && (top..top + draw_h).contains(&y);
+            let alive = on_board && self.grid[(y - top) * self.rows / draw_h][(x - left) * self.cols / draw_w].is_alive;
+            px.copy_from_slice(if alive { &fg } else { &bg });
+        }
+        Ok(())
+    }
+
+    /// Renders a `height` by `width` window into the board with its top left corner at (`top`, `left`).
+    ///
+    /// Just like `Display`, the screen is cleared and the window gets a border. Every line
+    /// is positioned with a cursor goto so this also works on a terminal in raw mode. The
+    /// window is clipped to the edges of the board.
+    pub fn render_viewport<W: Write>(&self, top: usize, left: usize, height: usize, width: usize, out: &mut W) -> io::Result<()> {
+        let height = height.min(self.rows.saturating_sub(top));
+        let width = width.min(self.cols.saturating_sub(left));
+        let right_border = self.screen_width(width) as u16 + 2;
+
+        // write top row of the border
+        write!(out, "{}{}┌", termion::clear::All, termion::cursor::Goto(1, 1))?;
+        for _ in 0..self.screen_width(width) {
+            write!(out, "─")?;
+        }
+        write!(out, "┐")?;
+
+        // write interior borders and cells
+        for (r, y) in (top..top + height).zip(2..) {
+            write!(out, "{}│", termion::cursor::Goto(1, y))?;
+            for c in left..left + width {
+                self.write_cell(out, r, c, self.screen_x(r, c - left), y)?;
+            }
+            write!(out, "{}│", termion::cursor::Goto(right_border, y))?;
+        }
+
+        // write bottom row of the border
+        write!(out, "{}└", termion::cursor::Goto(1, height as u16 + 2))?;
+        for _ in 0..self.screen_width(width) {
+            write!(out, "─")?;
+        }
+        write!(out, "┘")?;
+
+        Ok(())
+    }
+
+    /// Iterate over all of the cells on the board
+    ///
+    /// This allows you to know the (x, y) location of all cells and
+    /// if they are alive or not.
+    pub fn iter_cells(&self) -> impl Iterator<Item = ((usize, usize), &bool)> {
+        self.grid
+            .iter()
+            .enumerate()
+            .flat_map(|(x, row)| row.iter().enumerate().map(move |(y, column)| ((x, y), &column.is_alive)))
+    }
+
+    /// Moves the origin used by `get_cell` and `iter_cells_from_origin` to (`row`, `col`)
+    ///
+    /// Papers usually put (0, 0) at the middle of the board or the pattern, e.g.
+    /// `b.set_origin(b.rows / 2, b.cols / 2)`, with negative coordinates up and to the left.
+    /// Only those two methods use it. Everything else, `iter_cells` included, still counts
+    /// from the top left corner.
+    pub fn set_origin(&mut self, row: usize, col: usize) {
+        self.origin = (row, col);
+    }
+
+    /// returns the top left based (row, col) of the origin, (0, 0) unless moved by `set_origin`
+    pub fn origin(&self) -> (usize, usize) {
+        self.origin
+    }
+
+    /// returns whether the cell at (`row`, `col`) relative to the origin is alive, or `None`
+    /// if it's off the board
+    pub fn get_cell(&self, row: isize, col: isize) -> Option<bool> {
+        let r = usize::try_from(self.origin.0 as isize + row).ok()?;
+        let c = usize::try_from(self.origin.1 as isize + col).ok()?;
+        self.grid.get(r)?.get(c).map(|cell| cell.is_alive)
+    }
+
+    /// Like `iter_cells`, but with coordinates relative to the origin set by `set_origin`
+    pub fn iter_cells_from_origin(&self) -> impl Iterator<Item = ((isize, isize), bool)> + '_ {
+        let (r0, c0) = (self.origin.0 as isize, self.origin.1 as isize);
+        self.iter_cells().map(move |((r, c), alive)| ((r as isize - r0, c as isize - c0), *alive))
+    }
+
+    /// Iterate over all of the cells on the board along with how they changed since `prev`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `prev` is not the same size as this board.
+    pub fn iter_changes<'a>(&'a self, prev: &'a Board) -> impl Iterator<Item = ((usize, usize), ChangeKind)> + 'a {
+        assert!(
+            self.rows == prev.rows && self.cols == prev.cols,
+            "Boards must be the same size!"
+        );
+
+        (0..self.rows).flat_map(move |r| {
+            (0..self.cols).map(move |c| {
+                let kind = match (prev.grid[r][c].is_alive, self.grid[r][c].is_alive) {
+                    (false, true) => ChangeKind::Born,
+                    (true, false) => ChangeKind::Died,
+                    _ => ChangeKind::Unchanged,
+                };
+                ((r, c), kind)
+            })
+        })
+    }
+}
+
+impl Display for Board {
+
+    /// Displays the Game of Life board on a termial. See `Board::write_to`.
+    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
+        let mut buf: Vec<u8> = Vec::new();
+        self.write_to(&mut buf).map_err(|_| Error)?;
+        f.write_str(&String::from_utf8_lossy(&buf))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_iter() {
+        // configure the board with some known state
+        let mut b = Board::new(5, 5);
+        for (y, row) in b.grid.iter_mut().enumerate() {
+            for (x, cell) in row.iter_mut().enumerate() {
+                cell.is_alive = x == y;
+            }
+        }
+
+        // check to make sure that state matches in the iterator
+        for ((x, y), alive) in b.iter_cells() {
+            assert_eq!(*alive, x == y);
+        }
+    }
+
+    #[test]
+    fn test_empty_and_full() {
+        let b = Board::new(3, 4);
+        assert!(b.is_empty());
+        assert_eq!(b.grid.len(), 3);
+
+        let mut b = Board::full(3, 4);
+        assert!(!b.is_empty());
+        assert_eq!(b.get_num_alive_cells(), 12);
+
+        // a full board dies off everywhere except the corners
+        b.update();
+        assert_eq!(b.get_num_alive_cells(), 4);
+        b.clear();
+        assert!(b.is_empty());
+    }
+
+    #[test]
+    fn test_zero_dimensions() {
+        for &(rows, cols) in &[(0, 5), (5, 0), (0, 0)] {
+            let mut b = Board::new(rows, cols);
+            b.edges = EdgeMode::Torus;
+            b.initialize_random(1.);
+            b.update();
+            assert_eq!(b.get_num_alive_cells(), 0);
+            assert!(b.is_empty());
+            assert_eq!(b.live_bounding_box(), None);
+            assert!(b.components(Connectivity::Eight).is_empty());
+
+            let _ = b.to_string();
+            let mut out: Vec<u8> = Vec::new();
+            b.write_plain(&mut out).unwrap();
+            b.render_viewport(0, 0, rows, cols, &mut out).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_initialize_with() {
+        let mut a = Board::new(20, 20);
+        let mut b = Board::new(20, 20);
+        a.initialize_with(InitDistribution::Uniform(0.5), 42);
+        b.initialize_with(InitDistribution::Uniform(0.5), 42);
+        assert_eq!(a.iter_cells().collect::<Vec<_>>(), b.iter_cells().collect::<Vec<_>>());
+
+        a.initialize_with(InitDistribution::Uniform(1.), 1);
+        assert_eq!(a.get_num_alive_cells(), 400);
+        a.initialize_with(InitDistribution::Uniform(0.), 1);
+        assert!(a.is_empty());
+
+        a.initialize_with(InitDistribution::Checkerboard { on_density: 1., off_density: 0. }, 1);
+        assert_eq!(a.get_num_alive_cells(), 200);
+        assert!(a.grid[0][0].is_alive && !a.grid[0][1].is_alive);
+
+        // a tight gaussian only leaves the middle alive
+        a.initialize_with(InitDistribution::Gaussian { center_density: 1., sigma: 0.5 }, 1);
+        let (top, left, bottom, right) = a.live_bounding_box().unwrap();
+        assert!(top >= 8 && left >= 8 && bottom <= 11 && right <= 11);
+    }
+
+    #[test]
+    fn test_initialize_random_symmetric() {
+        let mut b = Board::new(7, 8);
+        let alive = |b: &Board, r: usize, c: usize| b.grid[r][c].is_alive;
+
+        b.initialize_random_symmetric(0.5, Symmetry::Both, 3);
+        for r in 0..7 {
+            for c in 0..8 {
+                assert_eq!(alive(&b, r, c), alive(&b, r, 7 - c));
+                assert_eq!(alive(&b, r, c), alive(&b, 6 - r, c));
+            }
+        }
+
+        b.initialize_random_symmetric(0.5, Symmetry::Rotational, 3);
+        for r in 0..7 {
+            for c in 0..8 {
+                assert_eq!(alive(&b, r, c), alive(&b, 6 - r, 7 - c));
+            }
+        }
+
+        let mut again = Board::new(7, 8);
+        again.initialize_random_symmetric(0.5, Symmetry::Rotational, 3);
+        assert_eq!(b.checksum(), again.checksum());
+    }
+
+    #[test]
+    fn test_births_and_deaths() {
+        // a blinker swaps two cells for two others every generation
+        let mut b = Board::new(5, 5);
+        for c in 1..4 {
+            b.grid[2][c].is_alive = true;
+        }
+        b.update();
+        assert_eq!((b.get_last_births(), b.get_last_deaths()), (2, 2));
+
+        // a lone cell just dies
+        let mut b = Board::new(3, 3);
+        b.grid[1][1].is_alive = true;
+        b.update();
+        assert_eq!((b.get_last_births(), b.get_last_deaths()), (0, 1));
+    }
+
+    #[test]
+    fn test_rule() {
+        let mut b = Board::new(2, 2);
+        assert_eq!(b.rule().to_string(), "B3/S23");
+        b.rule = "23/36".parse().unwrap();
+        assert_eq!(b.rule().to_string(), "B36/S23");
+    }
+
+    #[test]
+    fn test_tick() {
+        let mut b = Board::new(5, 5);
+        for c in 1..4 {
+            b.grid[2][c].is_alive = true;
+        }
+        assert_eq!(b.tick(), TickResult { generation: 1, births: 2, deaths: 2, changed: true });
+
+        let mut block = Board::new(4, 4);
+        for &(r, c) in &[(1, 1), (1, 2), (2, 1), (2, 2)] {
+            block.grid[r][c].is_alive = true;
+        }
+        assert_eq!(block.tick(), TickResult { generation: 1, births: 0, deaths: 0, changed: false });
+    }
+
+    #[test]
+    fn test_update_with() {
+        let mut b = Board::new(5, 5);
+        for c in 1..4 {
+            b.grid[2][c].is_alive = true;
+        }
+
+        let mut born = Vec::new();
+        let mut died = Vec::new();
+        b.update_with(|r, c| born.push((r, c)), |r, c| died.push((r, c)));
+        born.sort_unstable();
+        died.sort_unstable();
+        assert_eq!(born, vec![(1, 2), (3, 2)]);
+        assert_eq!(died, vec![(2, 1), (2, 3)]);
+        assert_eq!(b.get_last_births(), 2);
+    }
+
+    #[test]
+    fn test_stamp_config_oriented() {
+        // an L that's 3 tall and 2 wide is 2 tall and 3 wide on its side
+        let conf = Configuration::from_cells_str("O.\nO.\nOO").unwrap();
+        let mut b = Board::new(4, 4);
+        b.stamp_config_oriented(&conf, 1, 1, Orientation::Rotate90).unwrap();
+        let alive: Vec<(usize, usize)> = b.iter_cells().filter(|(_, a)| **a).map(|(rc, _)| rc).collect();
+        assert_eq!(alive, vec![(1, 1), (1, 2), (1, 3), (2, 1)]);
+
+        assert_eq!(b.stamp_config_oriented(&conf, 2, 0, Orientation::Rotate270), Ok(()));
+        assert_eq!(
+            b.stamp_config_oriented(&conf, 2, 0, Orientation::FlipVertical),
+            Err(ApplyError::TooManyRows { required: 5, available: 4 })
+        );
+        assert_eq!(
+            b.stamp_config_oriented(&conf, 0, 2, Orientation::Transpose),
+            Err(ApplyError::TooManyCols { required: 5, available: 4 })
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "parallel")]
+    fn test_update_parallel() {
+        let mut serial = Board::new(37, 23);
+        serial.initialize_with(InitDistribution::Uniform(0.4), 7);
+        serial.edges = EdgeMode::Torus;
+
+        for threads in &[1, 4, 7, 100] {
+            let mut parallel = serial.clone();
+            let mut serial = serial.clone();
+            for _ in 0..20 {
+                serial.update();
+                parallel.update_parallel(*threads);
+                assert_eq!(parallel.checksum(), serial.checksum(), "{} threads", threads);
+                assert_eq!((parallel.last_births, parallel.last_deaths), (serial.last_births, serial.last_deaths));
+            }
+        }
+
+        let mut brain = Board::new(10, 10);
+        brain.rule = "B2/S/C3".parse().unwrap();
+        brain.initialize_with(InitDistribution::Uniform(0.3), 1);
+        let mut parallel = brain.clone();
+        for _ in 0..10 {
+            brain.update();
+            parallel.update_parallel(3);
+        }
+        for r in 0..10 {
+            for c in 0..10 {
+                assert_eq!(parallel.grid[r][c].state(), brain.grid[r][c].state());
+            }
+        }
+
+        Board::new(0, 5).update_parallel(4);
+    }
+
+    #[test]
+    fn test_binary_snapshots() {
+        let mut b = Board::new(3, 5);
+        b.grid[0][1].is_alive = true;
+        b.grid[2][4].is_alive = true;
+        b.update();
+        b.grid[1][0].is_alive = true;
+        b.grid[2][4].is_alive = true;
+
+        let mut bytes = Vec::new();
+        b.write_binary(&mut bytes).unwrap();
+        assert_eq!(&bytes[..5], b"GoLB\x01");
+        assert_eq!(&bytes[5..17], &[3, 0, 0, 0, 5, 0, 0, 0, 1, 0, 0, 0]);
+        // cells 5 and 14
+        assert_eq!(&bytes[17..], &[0b0010_0000, 0b0100_0000]);
+
+        let read = Board::read_binary(&mut &bytes[..]).unwrap();
+        assert_eq!((read.rows, read.cols, read.get_generation()), (3, 5, 1));
+        assert_eq!(read.checksum(), b.checksum());
+
+        let read = |bytes: &[u8]| Board::read_binary(&mut &bytes[..]);
+        let snapshot = |rows: u32, cols: u32| {
+            let mut bytes = b"GoLB\x01".to_vec();
+            for field in &[rows, cols, 0] {
+                bytes.extend_from_slice(&field.to_le_bytes());
+            }
+            bytes
+        };
+        let mut empty_bytes = Vec::new();
+        Board::new(0, 0).write_binary(&mut empty_bytes).unwrap();
+        assert_eq!(empty_bytes, snapshot(0, 0));
+        assert_eq!(read(&empty_bytes).unwrap().rows, 0);
+
+        // sizes that would take forever to allocate are rejected up front
+        assert!(matches!(read(&snapshot(u32::MAX, 0)), Err(BinaryError::BadSize { rows: 4294967295, cols: 0 })));
+        assert!(matches!(read(&snapshot(0, 4)), Err(BinaryError::BadSize { .. })));
+        assert!(matches!(read(&snapshot(1 << 16, 1 << 16)), Err(BinaryError::BadSize { .. })));
+
+        assert!(matches!(read(b"nope, not a board"), Err(BinaryError::BadMagic)));
+        assert!(matches!(read(b"Go"), Err(BinaryError::BadMagic)));
+        assert!(matches!(read(b"GoLB"), Err(BinaryError::Truncated)));
+        assert!(matches!(read(&bytes[..10]), Err(BinaryError::Truncated)));
+        assert!(matches!(read(b"GoLB\x02"), Err(BinaryError::UnsupportedVersion(2))));
+        assert!(matches!(
+            read(&bytes[..18]),
+            Err(BinaryError::SizeMismatch { expected: 2, actual: 1 })
+        ));
+        let mut extra = bytes.clone();
+        extra.push(0);
+        assert!(matches!(read(&extra), Err(BinaryError::SizeMismatch { expected: 2, actual: 3 })));
+    }
+
+    #[test]
+    fn test_downsample() {
+        let mut b = Board::new(5, 5);
+        b.grid[0][0].is_alive = true;
+        b.grid[2][2].is_alive = true;
+        b.grid[2][3].is_alive = true;
+        b.grid[3][2].is_alive = true;
+        b.grid[4][4].is_alive = true;
+
+        let any = b.downsample(2, DownsamplePolicy::Any);
+        assert_eq!((any.rows, any.cols), (3, 3));
+        let alive: Vec<(usize, usize)> = any.iter_cells().filter(|(_, a)| **a).map(|(rc, _)| rc).collect();
+        assert_eq!(alive, vec![(0, 0), (1, 1), (2, 2)]);
+
+        // the corner block is cut down to a single alive cell, so it's a majority on its own
+        let majority = b.downsample(2, DownsamplePolicy::Majority);
+        let alive: Vec<(usize, usize)> = majority.iter_cells().filter(|(_, a)| **a).map(|(rc, _)| rc).collect();
+        assert_eq!(alive, vec![(1, 1), (2, 2)]);
+
+        assert_eq!(b.downsample(0, DownsamplePolicy::Any).get_num_alive_cells(), 5);
+        assert_eq!(b.downsample(10, DownsamplePolicy::Majority).get_num_alive_cells(), 0);
+    }
+
+    #[test]
+    fn test_render_to_framebuffer() {
+        const FG: [u8; 4] = [255, 255, 255, 255];
+        const BG: [u8; 4] = [0, 0, 0, 255];
+        let mut b = Board::new(2, 2);
+        b.grid[0][0].is_alive = true;
+        b.grid[1][1].is_alive = true;
+
+        let pixel = |buf: &[u8], w: usize, x: usize, y: usize| -> [u8; 4] {
+            let i = 4 * (y * w + x);
+            [buf[i], buf[i + 1], buf[i + 2], buf[i + 3]]
+        };
+
+        let mut buf = [7u8; 4 * 4 * 4];
+        b.render_to_framebuffer(&mut buf, 4, 4, FG, BG).unwrap();
+        assert_eq!(pixel(&buf, 4, 0, 0), FG);
+        assert_eq!(pixel(&buf, 4, 1, 1), FG);
+        assert_eq!(pixel(&buf, 4, 2, 1), BG);
+        assert_eq!(pixel(&buf, 4, 3, 3), FG);
+
+        // a wide framebuffer leaves bars on either side
+        let mut buf = vec![0u8; 8 * 4 * 4];
+        b.render_to_framebuffer(&mut buf, 8, 4, FG, BG).unwrap();
+        assert_eq!(pixel(&buf, 8, 0, 0), BG);
+        assert_eq!(pixel(&buf, 8, 2, 0), FG);
+        assert_eq!(pixel(&buf, 8, 5, 3), FG);
+        assert_eq!(pixel(&buf, 8, 7, 3), BG);
+
+        assert!(b.render_to_framebuffer(&mut buf, 8, 5, FG, BG).is_err());
+        Board::new(0, 3).render_to_framebuffer(&mut buf, 8, 4, FG, BG).unwrap();
+        assert!(buf.chunks(4).all(|px| px == BG));
+    }
+
+    #[test]
+    fn test_find_predecessor() {
+        let mut b = Board::new(5, 5);
+        for r in 1..4 {
+            b.grid[r][2].is_alive = true;
+        }
+        let mut prev = b.find_predecessor().unwrap().unwrap();
+        prev.update();
+        assert_eq!(prev.checksum(), b.checksum());
+        assert_eq!(b.is_garden_of_eden(), Ok(false));
+
+        // a lone cell can never have enough neighbors to be alive
+        let mut lone = Board::new(1, 1);
+        lone.grid[0][0].is_alive = true;
+        assert_eq!(lone.is_garden_of_eden(), Ok(true));
+        let mut row = Board::new(1, 3);
+        row.grid[0][0].is_alive = true;
+        assert_eq!(row.find_predecessor().unwrap().map(|p| p.checksum()), None);
+
+        // wrapped edges give every cell more neighbors to work with
+        let mut torus = Board::new(4, 4);
+        torus.edges = EdgeMode::Torus;
+        torus.grid[0][0].is_alive = true;
+        torus.grid[0][1].is_alive = true;
+        torus.grid[0][2].is_alive = true;
+        let mut prev = torus.find_predecessor().unwrap().expect("a row of three on a torus has a predecessor");
+        prev.update();
+        assert_eq!(prev.checksum(), torus.checksum());
+
+        // long thin boards are searched down the short side, but wrapped boards soon get too wide
+        let mut strip = Board::new(32, 2);
+        strip.grid[0][0].is_alive = true;
+        assert_eq!(strip.find_predecessor().unwrap().map(|p| p.checksum()), None);
+        let mut torus = Board::new(8, 8);
+        torus.edges = EdgeMode::Torus;
+        assert!(torus.find_predecessor().is_err());
+
+        assert!(Board::new(9, 8).find_predecessor().is_err());
+        let mut brain = Board::new(2, 2);
+        brain.rule = "B2/S/C3".parse().unwrap();
+        assert!(brain.is_garden_of_eden().is_err());
+    }
+
+    #[test]
+    fn test_history_rule() {
+        // cells alive last generation can't be born again, so a blinker burns out
+        let mut b = Board::new(5, 5);
+        b.stamp_rle("3o!", 2, 1).unwrap();
+        b.rule = "B3/S23/H1".parse().unwrap();
+        let (born, _) = b.preview_changes();
+        assert_eq!(born.len(), 2);
+        b.update();
+        assert_eq!(b.get_num_alive_cells(), 3);
+        assert_eq!(b.preview_changes().0, Vec::new());
+        b.update();
+        assert_eq!(b.get_num_alive_cells(), 1);
+        assert!(!b.history.is_empty());
+
+        // no cell of a glider is alive in every one of the last 32 generations, so it flies
+        // on as under Conway
+        let mut glider = Board::from_config(&Configuration::builtin("glider").unwrap(), 4);
+        let mut plain = glider.clone();
+        glider.rule = "B3/S23/H32:32".parse().unwrap();
+        for _ in 0..8 {
+            glider.update();
+            plain.update();
+        }
+        assert_eq!(glider.checksum(), plain.checksum());
+
+        // Conway boards keep no history at all
+        assert!(plain.history.is_empty());
+        glider.rule = Rule::conway();
+        glider.update();
+        assert!(glider.history.is_empty());
+
+        // dying states still play out
+        let mut brain = Board::new(4, 4);
+        brain.stamp_rle("2o!", 1, 1).unwrap();
+        brain.rule = "B2/S/C3/H2".parse().unwrap();
+        brain.update();
+        assert_eq!(brain.grid[1][1].state(), 2);
+        assert!(brain.find_predecessor().is_err());
+    }
+
+    #[test]
+    fn test_preview_changes() {
+        let mut b = Board::new(5, 5);
+        b.stamp_rle("3o!", 2, 1).unwrap();
+        let before = b.checksum();
+        let (born, died) = b.preview_changes();
+        assert_eq!(born, vec![(1, 2), (3, 2)]);
+        assert_eq!(died, vec![(2, 1), (2, 3)]);
+        assert_eq!(b.checksum(), before);
+        assert_eq!(b.get_generation(), 0);
+
+        // and it matches what the update does
+        let mut updated_born = Vec::new();
+        let mut updated_died = Vec::new();
+        b.update_with(|r, c| updated_born.push((r, c)), |r, c| updated_died.push((r, c)));
+        updated_born.sort_unstable();
+        updated_died.sort_unstable();
+        assert_eq!((updated_born, updated_died), (born, died));
+
+        // already dying cells just keep fading
+        let mut brain = Board::new(4, 4);
+        brain.rule = "B2/S/C3".parse().unwrap();
+        brain.stamp_rle("2o!", 1, 1).unwrap();
+        assert_eq!(brain.preview_changes().1, vec![(1, 1), (1, 2)]);
+        brain.update();
+        let (_, died) = brain.preview_changes();
+        assert!(!died.contains(&(1, 1)) && !died.contains(&(1, 2)));
+        // the cells born next to them die right away though, as nothing survives
+        assert_eq!(died, vec![(0, 1), (0, 2), (2, 1), (2, 2)]);
+    }
+
+    #[test]
+    fn test_stamp_rle() {
+        let mut b = Board::new(20, 20);
+        b.grid[0][0].is_alive = true;
+        b.stamp_rle("bo$2bo$3o!", 10, 10).unwrap();
+        assert_eq!(b.get_num_alive_cells(), 6);
+        assert!(b.grid[0][0].is_alive && b.grid[10][11].is_alive && b.grid[12][12].is_alive);
+
+        b.stamp_rle("#N block\nx = 2, y = 2\n2o$2o!", 0, 18).unwrap();
+        assert!(b.grid[1][19].is_alive);
+
+        // right up against the corner fits, one further doesn't
+        b.stamp_rle("bo$2bo$3o!", 17, 17).unwrap();
+        assert!(b.grid[19][19].is_alive);
+        assert!(b.stamp_rle("bo$2bo$3o!", 18, 17).is_err());
+        assert!(b.stamp_rle("o!", 20, 0).is_err());
+        assert!(b.stamp_rle("x = 2, y = 2\n2o$2o!", 19, 0).is_err());
+    }
+
+    #[test]
+    fn test_alive_in_region() {
+        let mut b = Board::new(6, 6);
+        b.grid[1][1].is_alive = true;
+        b.grid[4][5].is_alive = true;
+        assert_eq!(b.alive_in_region(0, 0, 6, 6), 2);
+        assert_eq!(b.alive_in_region(0, 0, 2, 2), 1);
+        assert_eq!(b.alive_in_region(2, 2, 4, 4), 0);
+        assert_eq!(b.alive_in_region(4, 5, 100, 100), 1);
+        assert_eq!(b.alive_in_region(3, 3, 3, 6), 0);
+        assert_eq!(b.alive_in_region(5, 0, 2, 6), 0);
+    }
+
+    #[test]
+    fn test_stamp_isolated() {
+        let glider = Configuration::builtin("glider").unwrap();
+        let mut b = Board::new(12, 12);
+        assert_eq!(b.stamp_isolated(&glider, 2), Ok((0, 0)));
+        // the first glider's bottom right cell is (2, 2), so the next one needs two dead cells past it
+        assert_eq!(b.stamp_isolated(&glider, 2), Ok((0, 5)));
+        // and there's no room left for a third on that row
+        assert_eq!(b.stamp_isolated(&glider, 2), Ok((5, 0)));
+        assert_eq!(b.stamp_isolated(&glider, 2), Ok((5, 5)));
+        assert_eq!(b.get_num_alive_cells(), 20);
+
+        // the gap includes the corners of the glider's box, even where it has no alive cells
+        let mut b = Board::new(5, 5);
+        b.grid[4][4].is_alive = true;
+        assert_eq!(b.stamp_isolated(&glider, 2), Err("No room on the board for the pattern!"));
+        assert_eq!(b.stamp_isolated(&glider, 1), Ok((0, 0)));
+
+        assert!(Board::new(2, 2).stamp_isolated(&glider, 0).is_err());
+    }
+
+    #[test]
+    fn test_distinct_states_visited() {
+        let mut block = Board::new(4, 4);
+        block.track_states = true;
+        Configuration::from_cells_str("OO\nOO").unwrap().apply_config_at(&mut block, 1, 1).unwrap();
+        assert_eq!(block.distinct_states_visited(), Some(1));
+        for _ in 0..5 {
+            block.update();
+        }
+        assert_eq!(block.distinct_states_visited(), Some(1));
+
+        let mut blinker = Board::from_config(&Configuration::builtin("blinker").unwrap(), 1);
+        blinker.track_states = true;
+        for _ in 0..7 {
+            blinker.update();
+        }
+        assert_eq!(blinker.distinct_states_visited(), Some(2));
+
+        let mut glider = Board::from_config(&Configuration::builtin("glider").unwrap(), 10);
+        glider.track_states = true;
+        for _ in 0..8 {
+            glider.update();
+        }
+        assert_eq!(glider.distinct_states_visited(), Some(9));
+
+        // nothing is kept unless asked for
+        let mut untracked = Board::from_config(&Configuration::builtin("glider").unwrap(), 10);
+        for _ in 0..8 {
+            untracked.update();
+        }
+        assert!(untracked.states.is_empty());
+        assert_eq!(untracked.distinct_states_visited(), None);
+
+        glider.clear();
+        assert_eq!(glider.distinct_states_visited(), Some(1));
+    }
+
+    #[test]
+    fn test_all_predecessors() {
+        // every 2x2 board dies out except the block and the four that grow into it
+        let empty = Board::new(2, 2);
+        let all = empty.all_predecessors(100).unwrap();
+        assert_eq!(all.len(), 11);
+        let mut checksums: Vec<u64> = all.iter().map(|b| b.checksum()).collect();
+        checksums.sort();
+        checksums.dedup();
+        assert_eq!(checksums.len(), 11);
+        for mut prev in all {
+            prev.update();
+            assert!(prev.is_empty());
+        }
+
+        assert_eq!(Board::new(3, 3).all_predecessors(5).unwrap().len(), 5);
+        assert!(Board::new(3, 3).all_predecessors(0).unwrap().is_empty());
+        let mut row = Board::new(1, 3);
+        row.grid[0][0].is_alive = true;
+        assert!(row.all_predecessors(10).unwrap().is_empty());
+        assert!(Board::new(9, 8).all_predecessors(1).is_err());
+    }
+
+    #[test]
+    fn test_run_until() {
+        let mut b = Board::new(8, 8);
+        Configuration::builtin("glider").unwrap().apply_config(&mut b).unwrap();
+
+        assert_eq!(b.run_until(|b| b.get_num_alive_cells() == 5, 10), Some(0));
+        assert_eq!(b.run_until(|b| (2..5).all(|c| b.grid[4][c].is_alive), 20), Some(8));
+        assert_eq!(b.get_generation(), 8);
+
+        assert_eq!(b.run_until(|b| b.get_num_alive_cells() > 5, 3), None);
+        assert_eq!(b.get_generation(), 11);
+    }
+
+    #[test]
+    fn test_dying_gradient() {
+        let mut b = Board::new(3, 3);
+        b.rule = Rule::generations(&[2], &[3, 4, 5], 6).unwrap();
+        b.grid[1][1].dying = 1;
+        b.grid[1][2].dying = 4;
+
+        let mut out: Vec<u8> = Vec::new();
+        b.write_to(&mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        let bright = termion::color::Fg(termion::color::AnsiValue::grayscale(20));
+        let dim = termion::color::Fg(termion::color::AnsiValue::grayscale(4));
+        assert!(out.contains(&format!("{}{}◍", termion::cursor::Goto(3, 3), bright)));
+        assert!(out.contains(&format!("{}{}◌", termion::cursor::Goto(4, 3), dim)));
+    }
+
+    #[test]
+    fn test_show_counts() {
+        let mut b = Board::new(5, 5);
+        for c in 1..4 {
+            b.grid[2][c].is_alive = true;
+        }
+        b.show_counts = true;
+        let mut out: Vec<u8> = Vec::new();
+        b.write_to(&mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+
+        // the ends of the blinker have one neighbor and the middle two, and the cells
+        // above and below the middle have three so they're drawn in green
+        assert!(!out.contains('●'));
+        assert!(out.contains(&format!("{}{}3", termion::cursor::Goto(4, 3), termion::color::Fg(termion::color::Green))));
+        assert!(out.contains(&format!("{}2", termion::cursor::Goto(4, 4))));
+        assert!(out.contains(&format!("{}1", termion::cursor::Goto(3, 4))));
+    }
+
+    #[test]
+    fn test_visited_cells() {
+        let mut b = Board::new(5, 5);
+        for c in 1..4 {
+            b.grid[2][c].is_alive = true;
+        }
+        assert_eq!(b.visited_cells().collect::<Vec<_>>(), vec![(2, 1), (2, 2), (2, 3)]);
+
+        b.update();
+        b.update();
+        assert_eq!(b.visited_cells().collect::<Vec<_>>(), vec![(1, 2), (2, 1), (2, 2), (2, 3), (3, 2)]);
+
+        b.trail = true;
+        b.grid[2][1].is_alive = false;
+        let mut out: Vec<u8> = Vec::new();
+        b.write_to(&mut out).unwrap();
+        assert!(String::from_utf8(out).unwrap().contains('░'));
+
+        b.clear();
+        assert_eq!(b.visited_cells().count(), 0);
+    }
+
+    #[test]
+    fn test_stamp_config_checked() {
+        let block = Configuration::from_cells_str("OO\nOO").unwrap();
+        let mut b = Board::new(4, 4);
+        b.stamp_config_checked(&block, 0, 0).unwrap();
+
+        assert!(b.would_overlap(&block, 1, 1));
+        assert!(!b.would_overlap(&block, 2, 2));
+        assert!(!b.would_overlap(&block, 3, 3));
+        assert_eq!(b.stamp_config_checked(&block, 1, 1), Err(ApplyError::Overlap(vec![(1, 1)])));
+        assert_eq!(b.stamp_config_checked(&block, 0, 1), Err(ApplyError::Overlap(vec![(0, 1), (1, 1)])));
+        assert_eq!(b.stamp_config_checked(&block, 3, 0), Err(ApplyError::TooManyRows { required: 5, available: 4 }));
+        assert_eq!(b.get_num_alive_cells(), 4);
+
+        b.stamp_config_checked(&block, 2, 2).unwrap();
+        assert_eq!(b.get_num_alive_cells(), 8);
+    }
+
+    #[test]
+    fn test_delta_from_initial() {
+        let mut b = Board::new(5, 5);
+        for c in 1..4 {
+            b.grid[2][c].is_alive = true;
+        }
+        assert_eq!(b.delta_from_initial(), (vec![], vec![]));
+
+        b.update();
+        assert_eq!(b.delta_from_initial(), (vec![(1, 2), (3, 2)], vec![(2, 1), (2, 3)]));
+        b.update();
+        assert_eq!(b.delta_from_initial(), (vec![], vec![]));
+
+        // a new pattern is a new generation 0
+        b.clear();
+        b.stamp_rle("2o$2o!", 0, 0).unwrap();
+        b.update();
+        assert_eq!(b.delta_from_initial(), (vec![], vec![]));
+        Configuration::from_rle_str("x = 3, y = 1\n3o!").unwrap().apply_config(&mut b).unwrap();
+        b.update();
+        assert_eq!(b.delta_from_initial(), (vec![(1, 1)], vec![(0, 0), (0, 2)]));
+    }
+
+    #[test]
+    fn test_lifespan_histogram() {
+        // the ends of a blinker only ever live for one generation, the center never dies
+        let mut b = Board::new(5, 5);
+        for c in 1..4 {
+            b.grid[2][c].is_alive = true;
+        }
+        for _ in 0..4 {
+            b.update();
+        }
+        assert_eq!(b.lifespan_histogram(), [(1, 8)].iter().copied().collect());
+
+        // a lone cell dies after its one generation
+        let mut b = Board::new(3, 3);
+        b.grid[1][1].is_alive = true;
+        b.update();
+        b.update();
+        assert_eq!(b.lifespan_histogram(), [(1, 1)].iter().copied().collect());
+
+        b.clear();
+        assert!(b.lifespan_histogram().is_empty());
+    }
+
+    #[test]
+    fn test_generations_rule() {
+        let mut b = Board::new(4, 4);
+        b.rule = "B2/S/C3".parse().unwrap();
+        b.grid[1][1].is_alive = true;
+        b.grid[1][2].is_alive = true;
+
+        b.update();
+        assert_eq!(b.get_last_deaths(), 2);
+        assert_eq!(b.get_num_alive_cells(), 4);
+        assert_eq!((b.grid[1][1].state(), b.grid[1][2].state()), (2, 2));
+
+        let mut out: Vec<u8> = Vec::new();
+        b.write_plain(&mut out).unwrap();
+        assert!(String::from_utf8(out).unwrap().contains("│ ◍◍ │"));
+
+        b.update();
+        assert_eq!((b.grid[1][1].state(), b.grid[1][2].state()), (0, 0));
+    }
+
+    #[test]
+    fn test_hex_neighbors() {
+        let mut b = Board::full(4, 4);
+        b.topology = Topology::Hex;
+
+        // interior cells of a hex grid have 6 neighbors no matter the row parity
+        assert_eq!(b.live_neighbors(1, 1), 6);
+        assert_eq!(b.live_neighbors(2, 2), 6);
+
+        // odd rows are shifted right, so this cell touches columns 2 and 3 above and below it
+        b.clear();
+        b.grid[1][2].is_alive = true;
+        assert_eq!(b.live_neighbors(0, 1), 0);
+        assert_eq!(b.live_neighbors(0, 2), 1);
+        assert_eq!(b.live_neighbors(0, 3), 1);
+        assert_eq!(b.live_neighbors(2, 1), 0);
+        assert_eq!(b.live_neighbors(2, 3), 1);
+    }
+
+    #[test]
+    fn test_population_after() {
+        // a lone block is a still life, a lone cell dies right away
+        let mut b = Board::new(6, 6);
+        b.grid[1][1].is_alive = true;
+        b.grid[1][2].is_alive = true;
+        b.grid[2][1].is_alive = true;
+        b.grid[2][2].is_alive = true;
+        b.grid[4][4].is_alive = true;
+
+        assert_eq!(b.population_after(0), 5);
+        assert_eq!(b.population_after(10), 4);
+        assert_eq!(b.get_generation(), 0);
+        assert_eq!(b.get_num_alive_cells(), 5);
+
+        let b = Board::full(1, 1);
+        assert_eq!(b.population_after(100), 0);
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let mut b = Board::new(3, 3);
+        b.grid[0][1].is_alive = true;
+
+        // corners only have three neighbors on the board
+        let corner: Vec<_> = b.neighbors(0, 0).collect();
+        assert_eq!(corner, vec![((0, 1), true), ((1, 0), false), ((1, 1), false)]);
+
+        assert_eq!(b.neighbors(1, 1).count(), 8);
+        assert_eq!(b.live_neighbors(1, 1), 1);
+        assert_eq!(b.live_neighbors(0, 1), 0);
+    }
+
+    #[test]
+    fn test_components() {
+        let conf = Configuration::from_cells_str("\
+OO...
+..O..
+.....
+...OO").unwrap();
+        let b = Board::from_config(&conf, 0);
+
+        assert_eq!(b.components(Connectivity::Eight), vec![
+            vec![(0, 0), (0, 1), (1, 2)],
+            vec![(3, 3), (3, 4)],
+        ]);
+        assert_eq!(b.components(Connectivity::Four), vec![
+            vec![(0, 0), (0, 1)],
+            vec![(1, 2)],
+            vec![(3, 3), (3, 4)],
+        ]);
+    }
+
+    #[test]
+    fn test_classify_objects() {
+        let conf = Configuration::from_cells_str("\
+OO.......
+OO.....O.
+.......O.
+.......O.
+...OO....
+..O..O...
+...OO....
+.........
+O.O......").unwrap();
+        let b = Board::from_config(&conf, 0);
+
+        let objects = b.classify_objects();
+        assert_eq!(objects, vec![
+            (String::from("block"), (0, 0)),
+            (String::from("blinker"), (1, 7)),
+            (String::from("beehive"), (4, 2)),
+            (String::from("unknown"), (8, 0)),
+            (String::from("unknown"), (8, 2)),
+        ]);
+    }
+
+    #[test]
+    fn test_edge_modes() {
+        let mut b = Board::new(3, 4);
+        b.grid[2][0].is_alive = true;
+
+        assert_eq!(b.live_neighbors(0, 3), 0);
+        b.edges = EdgeMode::Torus;
+        assert_eq!(b.live_neighbors(0, 3), 1);
+        b.edges = EdgeMode::Mobius;
+        assert_eq!(b.live_neighbors(0, 3), 1);
+        assert_eq!(b.live_neighbors(2, 3), 0);
+
+        // a glider on a torus comes back around to where it started
+        let conf = Configuration::from_cells_str(".O\n..O\nOOO").unwrap();
+        let mut b = Board::new(6, 6);
+        conf.apply_config(&mut b).unwrap();
+        b.edges = EdgeMode::Torus;
+        let start: Vec<_> = b.iter_cells().map(|(pos, alive)| (pos, *alive)).collect();
+        for _ in 0..24 {
+            b.update();
+        }
+        let end: Vec<_> = b.iter_cells().map(|(pos, alive)| (pos, *alive)).collect();
+        assert_eq!(start, end);
+    }
+
+    #[test]
+    fn test_delta() {
+        let conf = Configuration::from_cells_str(".O\n..O\nOOO").unwrap();
+        let mut b = Board::from_config(&conf, 2);
+        let mut mirror = b.clone();
+
+        for _ in 0..5 {
+            let prev = b.clone();
+            b.update();
+            let delta = b.delta(&prev);
+            assert!(delta.len() < b.rows * b.cols);
+
+            mirror.apply_delta(&delta).unwrap();
+            assert_eq!(mirror.checksum(), b.checksum());
+        }
+        assert_ne!(mirror.checksum(), Board::from_config(&conf, 2).checksum());
+        assert!(mirror.apply_delta(&[((100, 0), true)]).is_err());
+
+        // a dying cell that the delta brings back is just alive
+        let mut brain = Board::new(3, 3);
+        brain.rule = "B2/S/C3".parse().unwrap();
+        brain.toggle_cell(1, 1).unwrap();
+        brain.update();
+        assert_eq!(brain.grid[1][1].state(), 2);
+        brain.apply_delta(&[((1, 1), true)]).unwrap();
+        assert_eq!(brain.grid[1][1].state(), 1);
+        assert_eq!(brain.grid[1][1].age, 0);
+    }
+
+    #[test]
+    fn test_toggle_cell() {
+        let mut b = Board::new(2, 2);
+        b.toggle_cell(1, 0).unwrap();
+        assert!(b.grid[1][0].is_alive);
+        b.toggle_cell(1, 0).unwrap();
+        assert!(b.is_empty());
+        assert!(b.toggle_cell(2, 0).is_err());
+    }
+
+    #[test]
+    fn test_hamming_distance() {
+        let mut a = Board::new(3, 3);
+        let mut b = Board::new(3, 3);
+        assert_eq!(a.hamming_distance(&b), Ok(0));
+
+        a.grid[0][0].is_alive = true;
+        a.grid[1][1].is_alive = true;
+        b.grid[1][1].is_alive = true;
+        b.grid[2][2].is_alive = true;
+        assert_eq!(a.hamming_distance(&b), Ok(2));
+        assert_eq!(b.hamming_distance(&a), Ok(2));
+
+        assert!(a.hamming_distance(&Board::new(3, 4)).is_err());
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn test_from_image() {
+        let mut img = image::GrayImage::new(4, 2);
+        img.put_pixel(3, 1, image::Luma([200]));
+        img.put_pixel(0, 0, image::Luma([100]));
+
+        let b = Board::from_image(&img, 128);
+        assert_eq!((b.rows, b.cols), (2, 4));
+        assert_eq!(b.get_num_alive_cells(), 1);
+        assert!(b.grid[1][3].is_alive);
+
+        let img = image::GrayImage::from_pixel(400, 200, image::Luma([255]));
+        let b = Board::from_image_scaled(&img, 128, 40, 40);
+        assert_eq!((b.rows, b.cols), (20, 40));
+        assert_eq!(b.get_num_alive_cells(), 800);
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let mut a = Board::new(2, 2);
+        let mut b = Board::new(2, 2);
+        a.grid[0][0].is_alive = true;
+        a.grid[0][1].is_alive = true;
+        b.grid[0][1].is_alive = true;
+        b.grid[1][1].is_alive = true;
+
+        let mut u = Board::new(2, 2);
+        u.union(&a).unwrap();
+        u.union(&b).unwrap();
+        assert_eq!(u.get_num_alive_cells(), 3);
+
+        let mut i = Board::new(2, 2);
+        i.union(&a).unwrap();
+        i.intersection(&b).unwrap();
+        assert_eq!(i.get_num_alive_cells(), 1);
+        assert!(i.grid[0][1].is_alive);
+
+        a.difference(&b).unwrap();
+        assert_eq!(a.get_num_alive_cells(), 1);
+        assert!(a.grid[0][0].is_alive);
+
+        assert!(a.union(&Board::new(3, 2)).is_err());
+    }
+
+    #[test]
+    fn test_from_config() {
+        let conf = Configuration::from_json_str(r#"{"board": [[0, 1], [1, 1]]}"#).unwrap();
+
+        let b = Board::from_config(&conf, 0);
+        assert_eq!((b.rows, b.cols), (2, 2));
+        assert_eq!(b.get_num_alive_cells(), 3);
+
+        let b = Board::from_config(&conf, 2);
+        assert_eq!((b.rows, b.cols), (6, 6));
+        assert_eq!(b.get_num_alive_cells(), 3);
+        assert!(!b.grid[2][2].is_alive);
+        assert!(b.grid[2][3].is_alive);
+        assert!(b.grid[3][2].is_alive);
+    }
+
+    #[test]
+    fn test_extract_region() {
+        let mut b = Board::new(6, 6);
+        b.rule = "B36/S23".parse().unwrap();
+        let glider = Configuration::from_cells_str(".O\n..O\nOOO").unwrap();
+        glider.apply_config_at(&mut b, 2, 3).unwrap();
+
+        let conf = b.extract_region(2, 3, 5, 6).unwrap();
+        assert_eq!((conf.rows, conf.cols), (3, 3));
+        assert_eq!(conf.rule().unwrap(), b.rule);
+
+        let mut copy = Board::new(3, 3);
+        conf.apply_config(&mut copy).unwrap();
+        assert_eq!(copy.get_num_alive_cells(), 5);
+        assert!(copy.grid[0][1].is_alive && copy.grid[1][2].is_alive && copy.grid[2][0].is_alive);
+
+        let empty = b.extract_region(1, 1, 1, 4).unwrap();
+        assert_eq!((empty.rows, empty.cols), (0, 3));
+
+        assert!(b.extract_region(0, 0, 7, 2).is_err());
+        assert!(b.extract_region(3, 0, 2, 2).is_err());
+    }
+
+    #[test]
+    fn test_equals_translated() {
+        let mut b = Board::new(10, 10);
+        let glider = Configuration::from_cells_str(".O\n..O\nOOO").unwrap();
+        glider.apply_config_at(&mut b, 1, 1).unwrap();
+
+        let start = b.clone();
+        b.update();
+        assert_eq!(start.equals_translated(&b), None);
+        for _ in 0..3 {
+            b.update();
+        }
+        assert_eq!(start.equals_translated(&b), Some((1, 1)));
+        assert_eq!(b.equals_translated(&start), Some((-1, -1)));
+
+        assert_eq!(Board::new(3, 3).equals_translated(&Board::new(5, 5)), Some((0, 0)));
+        assert_eq!(start.equals_translated(&Board::new(5, 5)), None);
+    }
+
+    #[test]
+    fn test_stamp_relative() {
+        let mut b = Board::new(6, 6);
+        b.grid[0][0].is_alive = true;
+        let glider = Configuration::from_cells_str(".O\n..O\nOOO").unwrap();
+
+        // put the glider's bottom right cell on (4, 5)
+        b.stamp_relative(&glider, (4, 5), (2, 2)).unwrap();
+        assert!(b.grid[0][0].is_alive);
+        assert!(b.grid[2][4].is_alive && b.grid[3][5].is_alive && b.grid[4][3].is_alive);
+        assert_eq!(b.get_num_alive_cells(), 6);
+
+        assert_eq!(b.stamp_relative(&glider, (1, 3), (2, 0)), Err(ApplyError::OffBoard { row: -1, col: 3 }));
+        assert!(b.stamp_relative(&glider, (5, 5), (0, 0)).is_err());
+    }
+
+    #[test]
+    fn test_center_of_mass() {
+        let mut b = Board::new(5, 5);
+        assert_eq!(b.center_of_mass(), None);
+
+        b.grid[1][1].is_alive = true;
+        b.grid[1][2].is_alive = true;
+        b.grid[4][3].is_alive = true;
+        assert_eq!(b.center_of_mass(), Some((2., 2.)));
+    }
+
+    #[test]
+    fn test_origin() {
+        let mut b = Board::new(5, 7);
+        b.grid[1][2].is_alive = true;
+        b.grid[2][3].is_alive = true;
+        assert_eq!(b.origin(), (0, 0));
+        assert_eq!(b.get_cell(1, 2), Some(true));
+        assert_eq!(b.get_cell(-1, 0), None);
+
+        b.set_origin(b.rows / 2, b.cols / 2);
+        assert_eq!(b.origin(), (2, 3));
+        assert_eq!(b.get_cell(0, 0), Some(true));
+        assert_eq!(b.get_cell(-1, -1), Some(true));
+        assert_eq!(b.get_cell(-2, -3), Some(false));
+        assert_eq!(b.get_cell(-3, 0), None);
+        assert_eq!(b.get_cell(0, 4), None);
+
+        let alive: Vec<(isize, isize)> = b.iter_cells_from_origin().filter(|(_, a)| *a).map(|(rc, _)| rc).collect();
+        assert_eq!(alive, vec![(-1, -1), (0, 0)]);
+        assert_eq!(b.iter_cells_from_origin().next(), Some(((-2, -3), false)));
+        // top left based accessors don't move
+        assert!(b.iter_cells().any(|(rc, a)| rc == (1, 2) && *a));
+    }
+
+    #[test]
+    fn test_compact() {
+        let mut b = Board::new(20, 30);
+        b.grid[5][10].is_alive = true;
+        b.grid[7][12].is_alive = true;
+        b.set_origin(6, 11);
+        assert_eq!(b.compact(2), (3, 8));
+        assert_eq!((b.rows, b.cols), (7, 7));
+        let alive: Vec<(usize, usize)> = b.iter_cells().filter(|(_, a)| **a).map(|(rc, _)| rc).collect();
+        assert_eq!(alive, vec![(2, 2), (4, 4)]);
+        assert_eq!(b.get_cell(-1, -1), Some(true));
+
+        // the margin stops at the edges of the board
+        let mut b = Board::new(10, 10);
+        b.grid[0][1].is_alive = true;
+        b.grid[9][1].is_alive = true;
+        assert_eq!(b.compact(3), (0, 0));
+        assert_eq!((b.rows, b.cols), (10, 5));
+        b.update();
+        assert!(b.is_empty());
+
+        // hex boards keep their stagger
+        let mut b = Board::new(10, 10);
+        b.topology = Topology::Hex;
+        b.grid[5][5].is_alive = true;
+        assert_eq!(b.compact(0), (4, 5));
+        assert_eq!((b.rows, b.cols), (2, 1));
+
+        let mut b = Board::new(4, 4);
+        assert_eq!(b.compact(1), (4, 4));
+        assert_eq!((b.rows, b.cols), (0, 0));
+        b.update();
+    }
+
+    #[test]
+    fn test_count_escaping_gliders() {
+        // a glider heading down and right
+        let mut b = Board::new(8, 8);
+        b.stamp_rle("bo$2bo$3o!", 0, 0).unwrap();
+        let mut crossings = Vec::new();
+        for _ in 0..30 {
+            for &edge in &[Edge::Top, Edge::Bottom, Edge::Left, Edge::Right] {
+                if b.count_escaping_gliders(edge) > 0 {
+                    crossings.push((b.get_generation(), edge));
+                }
+            }
+            b.update();
+        }
+        // counted once, and it breaks up on the dead bottom edge before it reaches the right one
+        assert_eq!(crossings, vec![(20, Edge::Bottom)]);
+
+        // two gliders flipped to head up and left, and a block that isn't one
+        let mut b = Board::new(10, 10);
+        b.stamp_rle("3o$o$bo!", 0, 0).unwrap();
+        b.stamp_rle("3o$o$bo!", 0, 5).unwrap();
+        b.stamp_rle("2o$2o!", 8, 0).unwrap();
+        assert_eq!(b.count_escaping_gliders(Edge::Top), 2);
+        assert_eq!(b.count_escaping_gliders(Edge::Left), 0);
+        assert_eq!(b.count_escaping_gliders(Edge::Bottom), 0);
+
+        // the Gosper gun puts out a glider every 30 generations. Each one is taken off once it's
+        // counted, or the debris it leaves on the dead edge would wreck the next one
+        let mut b = Board::from_config(&Configuration::builtin("glider-gun").unwrap(), 10);
+        let mut bottom = Board::new(b.rows, b.cols);
+        bottom.stamp_rle(&format!("{0}o${0}o${0}o!", b.cols), b.rows - 3, 0).unwrap();
+        let mut crossings = Vec::new();
+        for _ in 0..200 {
+            if b.count_escaping_gliders(Edge::Bottom) > 0 {
+                crossings.push(b.get_generation());
+                b.difference(&bottom).unwrap();
+            }
+            b.update();
+        }
+        let gaps: Vec<usize> = crossings.windows(2).map(|w| w[1] - w[0]).collect();
+        assert!(crossings.len() >= 3, "{:?}", crossings);
+        assert!(gaps.iter().all(|gap| *gap == 30), "{:?}", crossings);
+    }
+
+    #[test]
+    fn test_expand_to_margin() {
+        let mut b = Board::new(5, 5);
+        assert_eq!(b.expand_to_margin(2), (0, 0));
+        b.stamp_rle("bo$2bo$3o!", 0, 2).unwrap();
+        b.set_origin(0, 2);
+
+        // only the sides that are too close grow, with some extra room
+        assert_eq!(b.expand_to_margin(1), (3, 0));
+        assert_eq!((b.rows, b.cols), (8, 8));
+        assert_eq!(b.origin(), (3, 2));
+        assert_eq!(b.get_num_alive_cells(), 5);
+        assert_eq!(b.get_cell(0, 1), Some(true));
+        assert_eq!(b.expand_to_margin(1), (0, 0));
+
+        // the glider never reaches an edge, however far it goes
+        for _ in 0..100 {
+            b.expand_to_margin(1);
+            b.update();
+        }
+        assert_eq!(b.get_num_alive_cells(), 5);
+        assert_eq!(b.classify_objects()[0].0, "glider");
+
+        let mut hex = Board::new(4, 4);
+        hex.topology = Topology::Hex;
+        hex.grid[0][1].is_alive = true;
+        assert_eq!(hex.expand_to_margin(1), (4, 0));
+    }
+
+    #[test]
+    fn test_torus_shift() {
+        let mut b = Board::new(6, 8);
+        b.stamp_rle("bo$2bo$3o!", 0, 0).unwrap();
+        assert!(b.torus_shift(1, 1).is_err());
+
+        b.edges = EdgeMode::Torus;
+        let mut moved = Board::new(6, 8);
+        moved.edges = EdgeMode::Torus;
+        moved.stamp_rle("bo$2bo$3o!", 0, 0).unwrap();
+        moved.set_origin(1, 1);
+        moved.torus_shift(-1, 7).unwrap();
+        assert_eq!(moved.origin(), (0, 0));
+        assert_eq!(moved.get_num_alive_cells(), 5);
+        let alive: Vec<(usize, usize)> = moved.iter_cells().filter(|(_, a)| **a).map(|(rc, _)| rc).collect();
+        assert_eq!(alive, vec![(0, 1), (1, 0), (1, 1), (1, 7), (5, 0)]);
+
+        // shifting then stepping is the same as stepping then shifting
+        for _ in 0..10 {
+            b.update();
+            moved.update();
+        }
+        b.torus_shift(5, 15).unwrap();
+        assert_eq!(b.hamming_distance(&moved), Ok(0));
+
+        // the states it has been through are still the same ones
+        let mut tracked = Board::new(6, 8);
+        tracked.edges = EdgeMode::Torus;
+        tracked.track_states = true;
+        tracked.stamp_rle("3o!", 2, 5).unwrap();
+        for _ in 0..3 {
+            tracked.update();
+            tracked.torus_shift(3, 5).unwrap();
+        }
+        assert_eq!(tracked.distinct_states_visited(), Some(2));
+
+        b.topology = Topology::Hex;
+        assert!(b.torus_shift(1, 0).is_err());
+        assert!(b.torus_shift(2, 0).is_ok());
+    }
+
+    #[test]
+    fn test_live_bounding_box() {
+        let mut b = Board::new(5, 5);
+        assert_eq!(b.live_bounding_box(), None);
+
+        b.grid[1][3].is_alive = true;
+        b.grid[3][1].is_alive = true;
+        assert_eq!(b.live_bounding_box(), Some((1, 1, 3, 3)));
+    }
+
+    #[test]
+    fn test_write_to_matches_display() {
+        let mut b = Board::new(3, 5);
+        b.grid[1][2].is_alive = true;
+
+        let mut out: Vec<u8> = Vec::new();
+        b.write_to(&mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), format!("{}", b));
+    }
+
+    #[test]
+    fn test_wrap_margin() {
+        let mut b = Board::new(3, 3);
+        b.grid[0][0].is_alive = true;
+        b.show_wrap_margin = 1;
+
+        // nothing wraps past dead edges
+        let mut out: Vec<u8> = Vec::new();
+        b.write_to(&mut out).unwrap();
+        assert!(!String::from_utf8(out).unwrap().contains(&termion::style::Faint.to_string()));
+
+        b.edges = EdgeMode::Torus;
+        let mut out: Vec<u8> = Vec::new();
+        b.write_to(&mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+
+        // the board itself moves over by one
+        assert!(out.contains(&format!("{}┌", termion::cursor::Goto(2, 2))));
+        assert!(out.contains(&format!("{}●", termion::cursor::Goto(3, 3))));
+
+        // and the corner cell shows up past the bottom, right and bottom right borders
+        for &(x, y) in &[(3, 7), (7, 3), (7, 7)] {
+            assert!(out.contains(&format!("{}{}●", termion::cursor::Goto(x, y), termion::style::Faint)));
+        }
+        assert_eq!(out.matches('●').count(), 4);
+    }
+
+    #[test]
+    fn test_heatmap() {
+        let mut b = Board::new(3, 4);
+        b.grid[1][1].is_alive = true;
+        b.heatmap = true;
+
+        let mut out: Vec<u8> = Vec::new();
+        b.write_to(&mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+
+        // the alive cell has no neighbors, the 8 around it have one each
+        let color = |n: usize| termion::color::Bg(termion::color::AnsiValue(HEATMAP_COLORS[n])).to_string();
+        assert!(out.contains(&format!("{}{}●", termion::cursor::Goto(3, 3), color(0))));
+        assert!(out.contains(&format!("{}{} ", termion::cursor::Goto(2, 2), color(1))));
+        assert_eq!(out.matches(&color(1)).count(), 8);
+        assert!(!out.contains(&termion::cursor::Goto(5, 3).to_string()));
+    }
+
+    #[test]
+    fn test_write_plain() {
+        let mut b = Board::new(2, 3);
+        b.grid[0][1].is_alive = true;
+        b.grid[1][2].is_alive = true;
+
+        let mut out: Vec<u8> = Vec::new();
+        b.write_plain(&mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "┌───┐\n│ ● │\n│  ●│\n└───┘\n");
+    }
+
+    #[test]
+    fn test_render_viewport() {
+        let mut b = Board::new(4, 4);
+        b.grid[0][0].is_alive = true;
+        b.grid[2][2].is_alive = true;
+
+        // only the lower right corner is in view
+        let mut out: Vec<u8> = Vec::new();
+        b.render_viewport(2, 2, 10, 10, &mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert_eq!(out.matches('●').count(), 1);
+        assert_eq!(out.matches('─').count(), 4);
+    }
+
+    #[test]
+    fn test_iter_changes() {
+        let mut prev = Board::new(1, 3);
+        let mut next = Board::new(1, 3);
+        prev.grid[0][0].is_alive = true;
+        prev.grid[0][1].is_alive = true;
+        next.grid[0][1].is_alive = true;
+        next.grid[0][2].is_alive = true;
+
+        let changes: Vec<_> = next.iter_changes(&prev).collect();
+        assert_eq!(changes, vec![
+            ((0, 0), ChangeKind::Died),
+            ((0, 1), ChangeKind::Unchanged),
+            ((0, 2), ChangeKind::Born),
+        ]);
     }
 }
\ No newline at end of file
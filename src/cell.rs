@@ -2,45 +2,79 @@
 ///
 use std::fmt::{Display, Formatter, Error};
 
+use crate::rule::Rule;
+
+/// Glyphs for the dying states of rules with more than two states, oldest last
+static DYING_GLYPHS: [char; 3] = ['◍', '○', '◌'];
+
 #[derive(Copy, Clone, Debug)]
 pub(crate) struct Cell {
     pub is_alive: bool,
+    /// How many generations a dead cell has been dying for under a rule with more than
+    /// two states, or 0 if it isn't dying. Dying cells don't count as alive neighbors
+    /// and can't be born.
+    pub(crate) dying: u8,
+    /// Number of generations in a row an alive cell has survived, 0 if it was just born or is dead
+    pub(crate) age: u32,
     pending_state: bool,
+    pending_dying: u8,
+    was_alive: bool,
 }
 
 impl Cell {
     /// returns a new, dead cell
     pub(crate) fn new() -> Cell {
-        Cell {is_alive: false, pending_state: false}
-    }
-
-    /// given the number of alive neighbors, update our pending state
-    pub(crate) fn update(&mut self, alive_neighbors: u32) {
-        if self.is_alive {
-            if alive_neighbors <= 1 || alive_neighbors > 3 {
-                self.pending_state = false;
-            } else {
-                self.pending_state = true;
-            }
-        } else if alive_neighbors == 3 {
-            self.pending_state = true;   // nature, uh, finds a way
+        Cell {is_alive: false, dying: 0, age: 0, pending_state: false, pending_dying: 0, was_alive: false}
+    }
+
+    /// returns the cell's state: 0 for dead, 1 for alive and 2 and up for each generation of dying
+    pub(crate) fn state(&self) -> u8 {
+        match (self.is_alive, self.dying) {
+            (true, _) => 1,
+            (false, 0) => 0,
+            (false, d) => d + 1,
         }
     }
 
+    /// given the number of alive neighbors and the rule in effect, update our pending state
+    pub(crate) fn update(&mut self, alive_neighbors: u32, rule: &Rule) {
+        self.set_pending(rule.next_cell_state(self.state(), alive_neighbors));
+    }
+
+    /// sets the state, as returned by `state`, that the cell will have once it's latched
+    pub(crate) fn set_pending(&mut self, next: u8) {
+        self.pending_state = next == 1;
+        self.pending_dying = next.saturating_sub(1);
+    }
+
     /// latches the pending internal state to alive or dead
     pub(crate) fn latch_state(&mut self) {
+        self.was_alive = self.is_alive;
         self.is_alive = self.pending_state;
+        self.dying = self.pending_dying;
+        self.age = if self.was_alive && self.is_alive { self.age.saturating_add(1) } else { 0 };
         self.pending_state = false;
+        self.pending_dying = 0;
+    }
+
+    /// returns what the cell is drawn as, or `None` if it's dead and not drawn at all
+    pub(crate) fn glyph(&self) -> Option<char> {
+        match self.state() {
+            0 => None,
+            1 => Some('●'),
+            s => Some(DYING_GLYPHS[(s as usize - 2).min(DYING_GLYPHS.len() - 1)]),
+        }
+    }
+
+    /// true if the cell was alive last generation but isn't anymore
+    pub(crate) fn just_died(&self) -> bool {
+        self.was_alive && !self.is_alive
     }
 }
 
 impl Display for Cell {
     fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
-        if self.is_alive {
-            write!(f, "●")
-        } else {
-            write!(f, " ")
-        }
+        write!(f, "{}", self.glyph().unwrap_or(' '))
     }
 }
 
@@ -63,8 +97,8 @@ mod test {
     #[test]
     fn new_cell() {
         let c = Cell::new();
-        assert!(c.is_alive == false);
-        assert!(c.pending_state == false);
+        assert!(!c.is_alive);
+        assert!(!c.pending_state);
     }
 
     #[test]
@@ -74,7 +108,7 @@ mod test {
         c.pending_state = true;
         c.latch_state();
 
-        assert!(c.is_alive == true);
+        assert!(c.is_alive);
     }
 
     #[test]
@@ -84,44 +118,97 @@ mod test {
         c.pending_state = false;
         c.latch_state();
 
-        assert!(c.is_alive == false);
+        assert!(!c.is_alive);
+    }
+
+    #[test]
+    fn cell_just_died() {
+        let mut c = get_new_alive_cell();
+        assert!(!c.just_died());
+
+        c.update(0, &Rule::conway());
+        c.latch_state();
+        assert!(c.just_died());
+
+        c.update(0, &Rule::conway());
+        c.latch_state();
+        assert!(!c.just_died());
+    }
+
+    #[test]
+    fn cell_age() {
+        let rule = Rule::conway();
+        let mut c = get_new_alive_cell();
+        for age in 1..4 {
+            c.update(2, &rule);
+            c.latch_state();
+            assert_eq!(c.age, age);
+        }
+
+        c.update(0, &rule);
+        c.latch_state();
+        assert_eq!(c.age, 0);
+        c.update(3, &rule);
+        c.latch_state();
+        assert_eq!(c.age, 0);
+    }
+
+    #[test]
+    fn cell_dying_states() {
+        // Brian's Brain: alive cells always die, then spend a generation dying
+        let rule: Rule = "B2/S/C3".parse().unwrap();
+        let mut c = get_new_alive_cell();
+
+        c.update(2, &rule);
+        c.latch_state();
+        assert_eq!(c.state(), 2);
+        assert!(!c.is_alive && c.just_died());
+
+        // a dying cell can't be born
+        c.update(2, &rule);
+        c.latch_state();
+        assert_eq!(c.state(), 0);
+
+        c.update(2, &rule);
+        c.latch_state();
+        assert_eq!(c.state(), 1);
     }
 
     #[test]
     fn cell_too_many_neighbors() {
-        let mut c = get_new_alive_cell(); c.update(4); assert!(c.pending_state == false);
-        let mut c = get_new_alive_cell(); c.update(5); assert!(c.pending_state == false);
-        let mut c = get_new_alive_cell(); c.update(6); assert!(c.pending_state == false);
-        let mut c = get_new_alive_cell(); c.update(7); assert!(c.pending_state == false);
-        let mut c = get_new_alive_cell(); c.update(8); assert!(c.pending_state == false);
+        let mut c = get_new_alive_cell(); c.update(4, &Rule::conway()); assert!(!c.pending_state);
+        let mut c = get_new_alive_cell(); c.update(5, &Rule::conway()); assert!(!c.pending_state);
+        let mut c = get_new_alive_cell(); c.update(6, &Rule::conway()); assert!(!c.pending_state);
+        let mut c = get_new_alive_cell(); c.update(7, &Rule::conway()); assert!(!c.pending_state);
+        let mut c = get_new_alive_cell(); c.update(8, &Rule::conway()); assert!(!c.pending_state);
     }
 
     #[test]
     fn cell_too_few_neighbors() {
-        let mut c = get_new_alive_cell(); c.update(1); assert!(c.pending_state == false);
-        let mut c = get_new_alive_cell(); c.update(0); assert!(c.pending_state == false);
+        let mut c = get_new_alive_cell(); c.update(1, &Rule::conway()); assert!(!c.pending_state);
+        let mut c = get_new_alive_cell(); c.update(0, &Rule::conway()); assert!(!c.pending_state);
     }
 
     #[test]
     fn cell_just_enough_neighbors() {
-        let mut c = get_new_alive_cell(); c.update(2); assert!(c.pending_state == true);
-        let mut c = get_new_alive_cell(); c.update(3); assert!(c.pending_state == true);
+        let mut c = get_new_alive_cell(); c.update(2, &Rule::conway()); assert!(c.pending_state);
+        let mut c = get_new_alive_cell(); c.update(3, &Rule::conway()); assert!(c.pending_state);
     }
 
     #[test]
     fn cell_reproductive_neighbors() {
         // no reproduction cases
-        let mut c = get_new_dead_cell(); c.update(0); assert!(c.pending_state == false);
-        let mut c = get_new_dead_cell(); c.update(1); assert!(c.pending_state == false);
-        let mut c = get_new_dead_cell(); c.update(2); assert!(c.pending_state == false);
-        let mut c = get_new_dead_cell(); c.update(4); assert!(c.pending_state == false);
-        let mut c = get_new_dead_cell(); c.update(5); assert!(c.pending_state == false);
-        let mut c = get_new_dead_cell(); c.update(6); assert!(c.pending_state == false);
-        let mut c = get_new_dead_cell(); c.update(7); assert!(c.pending_state == false);
-        let mut c = get_new_dead_cell(); c.update(8); assert!(c.pending_state == false);
+        let mut c = get_new_dead_cell(); c.update(0, &Rule::conway()); assert!(!c.pending_state);
+        let mut c = get_new_dead_cell(); c.update(1, &Rule::conway()); assert!(!c.pending_state);
+        let mut c = get_new_dead_cell(); c.update(2, &Rule::conway()); assert!(!c.pending_state);
+        let mut c = get_new_dead_cell(); c.update(4, &Rule::conway()); assert!(!c.pending_state);
+        let mut c = get_new_dead_cell(); c.update(5, &Rule::conway()); assert!(!c.pending_state);
+        let mut c = get_new_dead_cell(); c.update(6, &Rule::conway()); assert!(!c.pending_state);
+        let mut c = get_new_dead_cell(); c.update(7, &Rule::conway()); assert!(!c.pending_state);
+        let mut c = get_new_dead_cell(); c.update(8, &Rule::conway()); assert!(!c.pending_state);
 
         // only case where there should be reproduction
-        let mut c = get_new_dead_cell(); c.update(3); assert!(c.pending_state == true);
+        let mut c = get_new_dead_cell(); c.update(3, &Rule::conway()); assert!(c.pending_state);
 
     }
 }
\ No newline at end of file
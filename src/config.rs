@@ -4,105 +4,888 @@
 /// formats in the GoL community.
 ///
 use std::path::Path;
-use std::error::Error;
 use std::fs::File;
 use std::io::prelude::*;
+use std::io::BufReader;
 use std::result;
+use std::convert::TryFrom;
+use std::str::FromStr;
 
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
 use serde::{Deserialize, Serialize};
 use serde_json::Result;
 use regex::Regex;
 
-use crate::board::Board;
+use crate::board::{Board, Orientation};
+use crate::cycle::CycleDetector;
+use crate::objects;
+use crate::patterns;
+use crate::rule::{Rule, RuleError};
 
-#[derive(Serialize, Deserialize)]
+/// The formats a configuration can be written in
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Format {
+    /// My own JSON format
+    Json,
+    /// Standard Run Length Encoded (`.rle`) format
+    Rle,
+    /// Plaintext (`.cells`) format
+    Cells,
+    /// Life 1.06 (`.lif`) format, a list of alive cell coordinates
+    Life106,
+}
+
+impl Format {
+    /// Picks the format based on a file's extension. Anything unknown is assumed to be RLE.
+    pub fn from_path(filepath: &Path) -> Format {
+        match filepath.extension().and_then(|e| e.to_str()) {
+            Some("json") => Format::Json,
+            Some("cells") => Format::Cells,
+            Some("lif") | Some("life") => Format::Life106,
+            _ => Format::Rle,
+        }
+    }
+
+    /// Tries to guess the format from the contents of a configuration
+    pub fn infer(s: &str) -> Option<Format> {
+        let re_dimensions = Regex::new(r"^\s*x\s*=").unwrap();
+
+        if s.trim_start().starts_with('{') {
+            return Some(Format::Json);
+        }
+        if s.trim_start().starts_with("#Life 1.06") {
+            return Some(Format::Life106);
+        }
+
+        let mut lines = s.lines().map(|l| l.trim_end()).filter(|l| !l.is_empty());
+        let first = lines.clone().find(|l| !l.starts_with('#'))?;
+        if re_dimensions.is_match(first) {
+            Some(Format::Rle)
+        } else if lines.all(|l| l.starts_with('!') || l.chars().all(|c| c == '.' || c == 'O' || c == '*')) {
+            Some(Format::Cells)
+        } else {
+            None
+        }
+    }
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> result::Result<Format, String> {
+        match s.to_ascii_lowercase().as_str() {
+            "json" => Ok(Format::Json),
+            "rle" => Ok(Format::Rle),
+            "cells" => Ok(Format::Cells),
+            "life106" | "lif" => Ok(Format::Life106),
+            _ => Err(format!("Unknown configuration format: {}", s)),
+        }
+    }
+}
+
+/// Reasons a configuration couldn't be applied to a board
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ApplyError {
+    /// The configuration needs `required` rows but the board only has `available`
+    TooManyRows { required: usize, available: usize },
+    /// The configuration needs `required` columns but the board only has `available`
+    TooManyCols { required: usize, available: usize },
+    /// The configuration would have its top left corner at (`row`, `col`), above or left of the board
+    OffBoard { row: isize, col: isize },
+    /// The configuration has alive cells on top of these (row, col) cells that were already alive
+    Overlap(Vec<(usize, usize)>),
+}
+
+impl std::fmt::Display for ApplyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ApplyError::TooManyRows { required, available } => write!(
+                f,
+                "This configuration requires a larger board ({} rows, the board has {})!",
+                required, available
+            ),
+            ApplyError::TooManyCols { required, available } => write!(
+                f,
+                "This configuration requires a larger board ({} cols, the board has {})!",
+                required, available
+            ),
+            ApplyError::OffBoard { row, col } => write!(
+                f,
+                "This configuration would start off the board at ({}, {})!",
+                row, col
+            ),
+            ApplyError::Overlap(cells) => {
+                write!(f, "This configuration overlaps alive cells at")?;
+                for (row, col) in cells {
+                    write!(f, " ({}, {})", row, col)?;
+                }
+                write!(f, "!")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ApplyError {}
+
+/// Matches the `x = <cols>, y = <rows>` header line of an RLE pattern
+static RLE_DIMENSIONS: &str = r"\s*x\s*=\s*(\d+),\s*y\s*=\s*(\d+)";
+
+/// Matches the rule given in an RLE header line
+static RLE_RULE: &str = r"(?:type|rule)\s*=\s*([\w/]+)";
+
+/// What an RLE pattern says about itself before the cells, as read by `Configuration::peek_header`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HeaderInfo {
+    pub rows: usize,
+    pub cols: usize,
+    /// The rule from the header line, or an older `#r` line if the header doesn't have one.
+    /// The header wins if the two disagree
+    pub rule: Option<String>,
+    /// The rule from the `#r` line if it disagrees with the header's
+    pub rule_conflict: Option<String>,
+    /// The pattern's name from its `#N` line
+    pub name: Option<String>,
+}
+
+/// How much room a pattern needs to run without reaching the edges, as found by
+/// `Configuration::required_bounds`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct RequiredBounds {
+    pub rows: usize,
+    pub cols: usize,
+    /// true if the pattern settled into a cycle that stays in place, so running it for longer
+    /// won't need any more room. False for patterns that keep growing or moving
+    pub stable: bool,
+}
+
+/// Longest line `Configuration::to_rle_string` writes, as recommended by the RLE format
+const RLE_LINE_LENGTH: usize = 70;
+
+/// Longest period `Configuration::apgcode` looks for
+pub const APGCODE_MAX_PERIOD: usize = 32;
+
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Configuration {
+    #[serde(default)]
     pub rows: usize,
+    #[serde(default)]
     pub cols: usize,
-    board: Vec<Vec<u8>>,
+    /// The rule given in the configuration, if any. Conway's rule is assumed otherwise
+    #[serde(default)]
+    pub rule: Option<String>,
+    /// Where the pattern's top left corner goes as (row, col) from the middle of the board, as
+    /// given by an RLE `#P x y` or `#R x y` line. Used by `apply_config`
+    #[serde(default)]
+    pub origin: Option<(i64, i64)>,
+    /// The rule from an RLE pattern's `#r` line if it disagrees with the one in its header,
+    /// which is the one used
+    #[serde(skip)]
+    pub rule_conflict: Option<String>,
+    pub(crate) board: Vec<Vec<u8>>,
 }
 
 impl Configuration {
-    pub fn load_json_config(filepath: &Path) -> Result<Configuration> {
-        let display = filepath.display();
-
-        // Open the path in read-only mode, returns `io::Result<File>`
-        let mut file = match File::open(&filepath) {
-            // The `description` method of `io::Error` returns a string that
-            // describes the error
-            Err(why) => panic!("couldn't open {}: {}", display, why.description()),
-            Ok(file) => file,
-        };
+    /// Loads a configuration, picking the parser based on the file extension.
+    ///
+    /// `.json` files use my own format, `.cells` files use the plaintext format,
+    /// `.lif` files use Life 1.06 and everything else is assumed to be RLE.
+    pub fn load(filepath: &Path) -> result::Result<Configuration, String> {
+        let s = std::fs::read_to_string(filepath).map_err(|why| format!("couldn't read {}: {}", filepath.display(), why))?;
+        Configuration::parse(&s, Format::from_path(filepath))
+    }
+
+    /// Reads just the name, rule and dimensions of the RLE pattern at `filepath`, stopping at
+    /// the header line so the cells are never parsed
+    pub fn peek_header(filepath: &Path) -> result::Result<HeaderInfo, String> {
+        let file = File::open(filepath).map_err(|why| format!("couldn't open {}: {}", filepath.display(), why))?;
+        read_rle_header(BufReader::new(file)).map_err(String::from)
+    }
+
+    /// Parses a configuration string in the given format
+    pub fn parse(s: &str, format: Format) -> result::Result<Configuration, String> {
+        match format {
+            Format::Json => Configuration::from_json_str(s).map_err(|e| e.to_string()),
+            Format::Rle => Configuration::from_rle_str(s).map_err(String::from),
+            Format::Cells => Configuration::from_cells_str(s).map_err(String::from),
+            Format::Life106 => Configuration::from_life106_str(s, DEFAULT_MAX_DENSE_CELLS).map_err(String::from),
+        }
+    }
+
+    /// Loads every frame of a sequence written by `app::record_rle`, in order
+    pub fn load_sequence(filepath: &Path) -> result::Result<Vec<Configuration>, String> {
+        let s = std::fs::read_to_string(filepath).map_err(|why| format!("couldn't read {}: {}", filepath.display(), why))?;
+        Configuration::parse_sequence(&s)
+    }
 
-        // Read the file contents into a string, returns `io::Result<usize>`
-        let mut s = String::new();
-        if let Err(why) = file.read_to_string(&mut s) {
-            panic!("couldn't read {}: {}", display, why.description());
+    /// Parses a sequence of RLE patterns, one after another. Each pattern ends with the `!`
+    /// that ends its board, and any comments before a pattern's header belong to it.
+    pub fn parse_sequence(s: &str) -> result::Result<Vec<Configuration>, String> {
+        let mut frames = Vec::new();
+        let mut frame = String::new();
+        for line in s.lines() {
+            frame.push_str(line);
+            frame.push('\n');
+            if !line.starts_with('#') && line.trim_end().ends_with('!') {
+                frames.push(Configuration::from_rle_str(&frame).map_err(String::from)?);
+                frame.clear();
+            }
+        }
+        if !frame.trim().is_empty() {
+            return Err(String::from("Sequence ends in the middle of a pattern!"));
         }
+        Ok(frames)
+    }
+
+    pub fn load_json_config(filepath: &Path) -> Result<Configuration> {
+        let s = read_file(filepath);
+        Configuration::from_json_str(&s)
+    }
+
+    /// Parses a configuration in my own JSON format
+    pub fn from_json_str(s: &str) -> Result<Configuration> {
+        let mut c: Configuration = serde_json::from_str(s)?;
 
-        let c: Configuration = serde_json::from_str(&s)?;
+        // rows and cols are optional in the file, so fill them in from the board
+        c.rows = c.rows.max(c.board.len());
+        c.cols = c.cols.max(c.board.iter().map(|row| row.len()).max().unwrap_or(0));
         Ok(c)
     }
 
     pub fn load_rle_config(filepath: &Path) -> result::Result<Configuration, &'static str> {
-        let display = filepath.display();
-
-        // Open the path in read-only mode, returns `io::Result<File>`
-        let mut file = match File::open(&filepath) {
-            // The `description` method of `io::Error` returns a string that
-            // describes the error
-            Err(why) => panic!("couldn't open {}: {}", display, why.description()),
-            Ok(file) => file,
-        };
+        Configuration::from_rle_str(&read_file(filepath))
+    }
+
+    /// Parses a configuration in the standard RLE format
+    pub fn from_rle_str(s: &str) -> result::Result<Configuration, &'static str> {
+        let (vec, rule) = parse_rle_string(s)?;
+        let mut c = Configuration::from_vec(vec)?;
+        c.rule = rule;
+        c.origin = parse_rle_position(s);
+        c.rule_conflict = read_rle_header(s.as_bytes()).ok().and_then(|header| header.rule_conflict);
+        Ok(c)
+    }
+
+    pub fn load_cells_config(filepath: &Path) -> result::Result<Configuration, &'static str> {
+        Configuration::from_cells_str(&read_file(filepath))
+    }
 
-        // Read the file contents into a string, returns `io::Result<usize>`
-        let mut s = String::new();
-        if let Err(why) = file.read_to_string(&mut s) {
-            panic!("couldn't read {}: {}", display, why.description());
+    /// Parses a configuration in the plaintext (`.cells`) format
+    pub fn from_cells_str(s: &str) -> result::Result<Configuration, &'static str> {
+        let vec = parse_cells_string(s)?;
+        Configuration::from_vec(vec)
+    }
+
+    /// Parses a configuration in the Life 1.06 format, a list of `x y` coordinates of alive cells.
+    ///
+    /// The coordinates can be negative and far apart, so the pattern is moved to start at
+    /// (0, 0). Patterns whose bounding box has more than `max_cells` cells are rejected
+    /// rather than building a huge board, see `SparseBoard::from_life106_str` for those.
+    pub fn from_life106_str(s: &str, max_cells: usize) -> result::Result<Configuration, &'static str> {
+        let cells = parse_life106_string(s)?;
+        let (top, left, rows, cols) = life106_bounds(&cells).ok_or("Configuration does not describe a board!")?;
+        if rows.checked_mul(cols).map_or(true, |n| n > max_cells) {
+            return Err("Pattern too large for a dense board, use SparseBoard::from_life106_str instead!");
+        }
+
+        let mut vec = vec![vec![0; cols]; rows];
+        for (r, c) in cells {
+            vec[(r - top) as usize][(c - left) as usize] = 1;
         }
+        Configuration::from_vec(vec)
+    }
 
-        let vec = parse_rle_string(&s)?;
+    fn from_vec(vec: Vec<Vec<u8>>) -> result::Result<Configuration, &'static str> {
+        if vec.is_empty() {
+            return Err("Configuration does not describe a board!");
+        }
 
         Ok(Configuration {
             rows: vec.len(),
             cols: vec[0].len(),
+            rule: None,
+            origin: None,
+            rule_conflict: None,
             board: vec,
         })
     }
 
-    pub fn apply_config(&self, board: &mut Board) -> result::Result<(), &'static str> {
-        // first, make sure that the config given can fit within the given board
-        if self.board.len() > board.rows {
-            return Err("This configuration requires a larger board (more rows)!")
+    /// Loads one of the classic patterns built into the crate by name, like `glider`
+    pub fn builtin(name: &str) -> Option<Configuration> {
+        patterns::BUILTIN
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, rle)| Configuration::from_rle_str(rle).unwrap())
+    }
+
+    /// returns the names of all the patterns `builtin` knows
+    pub fn builtin_names() -> Vec<&'static str> {
+        patterns::BUILTIN.iter().map(|(name, _)| *name).collect()
+    }
+
+    /// returns the cell at (`row`, `col`), 1 for alive and 0 for dead, or `None` if it's out of bounds
+    pub fn cell(&self, row: usize, col: usize) -> Option<u8> {
+        self.board.get(row)?.get(col).copied()
+    }
+
+    /// Sets the cell at (`row`, `col`) to `val`, where anything but 0 is alive
+    pub fn set_cell(&mut self, row: usize, col: usize, val: u8) -> result::Result<(), &'static str> {
+        match self.board.get_mut(row).and_then(|r| r.get_mut(col)) {
+            Some(cell) => {
+                *cell = val;
+                Ok(())
+            }
+            None => Err("Cell is outside of the configuration!"),
+        }
+    }
+
+    /// returns all of the cells as rows of 1s and 0s
+    pub fn as_grid(&self) -> &Vec<Vec<u8>> {
+        &self.board
+    }
+
+    /// Writes the configuration in the standard RLE format
+    pub fn to_rle_string(&self) -> String {
+        let cols = self.board.iter().map(|r| r.len()).max().unwrap_or(0);
+        let mut out = format!("x = {}, y = {}", cols, self.board.len());
+        if let Some(rule) = &self.rule {
+            out.push_str(&format!(", rule = {}", rule));
+        }
+        out.push('\n');
+
+        // each token is a run like `3o`, kept whole so lines can be wrapped between them
+        let mut tokens: Vec<String> = Vec::new();
+        fn push(tokens: &mut Vec<String>, n: usize, tag: char) {
+            if n == 1 {
+                tokens.push(tag.to_string());
+            } else if n > 1 {
+                tokens.push(format!("{}{}", n, tag));
+            }
         }
+        let mut blank_rows = 0;
         for row in &self.board {
-            if row.len() > board.cols {
-                eprintln!("conf cols: {}, board cols: {}", row.len(), board.cols);
-                return Err("This configuration requires a larger board (more cols)!")
+            let alive: Vec<bool> = row.iter().map(|v| *v != 0).collect();
+            let end = match alive.iter().rposition(|a| *a) {
+                Some(last) => last + 1,
+                None => {
+                    blank_rows += 1;
+                    continue;
+                }
+            };
+            // the rows before the first alive one don't need ending
+            let row_ends = if tokens.is_empty() { blank_rows } else { blank_rows + 1 };
+            push(&mut tokens, row_ends, '$');
+            blank_rows = 0;
+
+            let mut c = 0;
+            while c < end {
+                let run = alive[c..end].iter().take_while(|a| **a == alive[c]).count();
+                push(&mut tokens, run, if alive[c] { 'o' } else { 'b' });
+                c += run;
             }
         }
+        tokens.push(String::from("!"));
+
+        let mut line = String::new();
+        for token in tokens {
+            if line.len() + token.len() > RLE_LINE_LENGTH {
+                out.push_str(&line);
+                out.push('\n');
+                line.clear();
+            }
+            line.push_str(&token);
+        }
+        out.push_str(&line);
+        out.push('\n');
+        out
+    }
+
+    /// Parses the rule given in the configuration, defaulting to Conway's if there wasn't one
+    pub fn rule(&self) -> result::Result<Rule, String> {
+        match &self.rule {
+            Some(r) => r.parse().map_err(|e: RuleError| e.to_string()),
+            None => Ok(Rule::conway()),
+        }
+    }
+
+    /// Clears the board and applies the configuration to it
+    ///
+    /// The pattern goes in the top left corner, or at its `origin` from the middle of the
+    /// board if it has one, moved back onto the board if it would hang off the edge. Use
+    /// `apply_config_at` to put it somewhere else; an explicit position always wins over `origin`.
+    pub fn apply_config(&self, board: &mut Board) -> result::Result<(), ApplyError> {
+        // first, make sure that the config given can fit within the given board
+        self.check_fits(board, 0, 0)?;
+        let (row, col) = self.placement(board);
 
         // apply the configuration!
         board.clear();
-        for (x, row) in self.board.iter().enumerate() {
-            for (y, val) in row.iter().enumerate() {
-                if *val != 0 {
-                    board.grid[x][y].is_alive = true;
+        self.stamp(board, row, col);
+
+        Ok(())
+    }
+
+    /// returns where `apply_config` puts the top left corner of the pattern on `board`
+    fn placement(&self, board: &Board) -> (usize, usize) {
+        let (row, col) = match self.origin {
+            Some(origin) => origin,
+            None => return (0, 0),
+        };
+        let widest = self.board.iter().map(|r| r.len()).max().unwrap_or(0);
+        let fit = |middle: usize, offset: i64, room: usize| (middle as i64 + offset).clamp(0, room as i64) as usize;
+        (
+            fit(board.rows / 2, row, board.rows.saturating_sub(self.board.len())),
+            fit(board.cols / 2, col, board.cols.saturating_sub(widest)),
+        )
+    }
+
+    /// Computes the apgcode of the pattern, the canonical name the Life community uses for objects
+    ///
+    /// Still lifes are `xs<population>_...`, oscillators are `xp<period>_...` and spaceships
+    /// `xq<period>_...`. Patterns that don't repeat within `APGCODE_MAX_PERIOD` generations
+    /// are `PATHOLOGICAL`.
+    pub fn apgcode(&self) -> String {
+        match self.run_until_repeat(APGCODE_MAX_PERIOD) {
+            Some((_, _, phases)) if phases[0].is_empty() => String::from("xs0_0"),
+            Some((period, moved, phases)) => {
+                let code = objects::canonical_wechsler(&phases);
+                if moved {
+                    format!("xq{}_{}", period, code)
+                } else if period == 1 {
+                    format!("xs{}_{}", phases[0].len(), code)
+                } else {
+                    format!("xp{}_{}", period, code)
                 }
             }
+            None => String::from("PATHOLOGICAL"),
+        }
+    }
+
+    /// returns the number of generations it takes the pattern to come back to its starting
+    /// shape, or None if it doesn't within `max_gen` generations
+    ///
+    /// Spaceships count as repeating once they're back in the same shape, wherever they've
+    /// moved to. Still lifes (and the empty pattern) have a period of 1.
+    pub fn period(&self, max_gen: usize) -> Option<usize> {
+        self.run_until_repeat(max_gen).map(|(period, _, _)| period)
+    }
+
+    /// true if the pattern is back exactly where it started after `p` generations and not before
+    ///
+    /// Unlike `period`, the cells have to be in the same place, so spaceships never pass. Any
+    /// earlier return, like a period 2 oscillator at `p` 4, means `p` isn't its exact period.
+    /// Still lifes are oscillators of period 1, the empty pattern isn't an oscillator at all.
+    pub fn is_oscillator_of_period(&self, p: usize) -> bool {
+        let mut board = Board::from_config(self, 1);
+        board.rule = self.rule().unwrap_or_default();
+        let start = alive_from_origin(&board);
+        if p == 0 || start.is_empty() {
+            return false;
+        }
+
+        for gen in 1..=p {
+            board.expand_to_margin(1);
+            board.update();
+            if alive_from_origin(&board) == start {
+                return gen == p;
+            }
+        }
+        false
+    }
+
+    /// Runs the pattern for up to `max_gen` generations and returns the size of the smallest
+    /// board that holds every cell that was alive at any point
+    ///
+    /// The run stops early once the board repeats a state it's been in before. Patterns that
+    /// never do are measured over all `max_gen` generations and aren't `stable`.
+    pub fn required_bounds(&self, max_gen: usize) -> RequiredBounds {
+        let mut board = Board::from_config(self, 1);
+        board.rule = self.rule().unwrap_or_default();
+
+        let (mut top, mut left, mut bottom, mut right) = (isize::MAX, isize::MAX, isize::MIN, isize::MIN);
+        let mut seen = CycleDetector::new();
+        let mut stable = false;
+        for gen in 0..=max_gen {
+            for (r, c) in alive_from_origin(&board) {
+                top = top.min(r);
+                left = left.min(c);
+                bottom = bottom.max(r);
+                right = right.max(c);
+            }
+            if seen.observe(&board).is_some() {
+                stable = true;
+                break;
+            }
+            if gen < max_gen {
+                board.expand_to_margin(1);
+                board.update();
+            }
+        }
+
+        if top == isize::MAX {
+            return RequiredBounds { rows: 0, cols: 0, stable };
+        }
+        RequiredBounds { rows: (bottom - top + 1) as usize, cols: (right - left + 1) as usize, stable }
+    }
+
+    /// Runs the pattern on a board that grows so nothing ever reaches the edge, until it's back
+    /// in its starting shape or `max_gen` generations have gone by.
+    ///
+    /// returns the period, whether the pattern moved, and the alive cells of every phase
+    fn run_until_repeat(&self, max_gen: usize) -> Option<(usize, bool, Vec<objects::Shape>)> {
+        let mut board = Board::from_config(self, 1);
+        board.rule = self.rule().unwrap_or_default();
+
+        let start = alive_from_origin(&board);
+        let corner = |shape: &objects::Shape| shape.iter().map(|(r, _)| *r).min().zip(shape.iter().map(|(_, c)| *c).min());
+        let start_corner = corner(&start);
+        let start_shape = objects::normalize(start.clone());
+
+        let mut phases = vec![start.clone()];
+        for period in 1..=max_gen {
+            board.expand_to_margin(1);
+            board.update();
+            let now = alive_from_origin(&board);
+            if now.len() == start.len() && objects::normalize(now.clone()) == start_shape {
+                return Some((period, corner(&now) != start_corner, phases));
+            }
+            phases.push(now);
+        }
+        None
+    }
+
+    /// returns a copy of the configuration rotated or reflected by `orientation`
+    ///
+    /// Quarter turns and diagonal reflections swap `rows` and `cols`.
+    pub fn oriented(&self, orientation: Orientation) -> Configuration {
+        let rows = self.board.len();
+        let cols = self.board.iter().map(|r| r.len()).max().unwrap_or(0);
+        let (new_rows, new_cols) = if orientation.swaps_dimensions() { (cols, rows) } else { (rows, cols) };
+
+        let board = (0..new_rows)
+            .map(|r| {
+                (0..new_cols)
+                    .map(|c| {
+                        let (sr, sc) = orientation.source(r, c, rows, cols);
+                        self.board[sr].get(sc).copied().unwrap_or(0)
+                    })
+                    .collect()
+            })
+            .collect();
+        Configuration {
+            rows: new_rows,
+            cols: new_cols,
+            rule: self.rule.clone(),
+            origin: self.origin,
+            rule_conflict: self.rule_conflict.clone(),
+            board,
+        }
+    }
+
+    /// Flips each cell, alive to dead or dead to alive, with probability `flip_prob`
+    ///
+    /// The same `seed` always flips the same cells, so searches built on it can be reproduced.
+    pub fn mutate(&mut self, flip_prob: f32, seed: u64) {
+        let mut rng = StdRng::seed_from_u64(seed);
+        for cell in self.board.iter_mut().flatten() {
+            if rng.gen::<f32>() < flip_prob {
+                *cell = (*cell == 0) as u8;
+            }
+        }
+    }
+
+    /// returns `count` near copies of the configuration, each with one small change: an alive
+    /// cell nudged to a dead spot next to it, a cell brought to life or an alive cell removed
+    ///
+    /// Every copy keeps the same size, so the changes stay inside the pattern's box. For looking
+    /// around a promising pattern, where `mutate` would change too much at once. The same
+    /// `seed` always makes the same copies.
+    pub fn jittered_variants(&self, count: usize, seed: u64) -> Vec<Configuration> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        (0..count)
+            .map(|_| {
+                let mut variant = self.clone();
+                variant.jitter(&mut rng);
+                variant
+            })
+            .collect()
+    }
+
+    /// Makes one of the small changes described in `jittered_variants`, trying a few times to
+    /// find one that changes something
+    fn jitter(&mut self, rng: &mut StdRng) {
+        let cells: Vec<(usize, usize)> =
+            self.board.iter().enumerate().flat_map(|(r, row)| (0..row.len()).map(move |c| (r, c))).collect();
+        if cells.is_empty() {
+            return;
         }
+        let alive: Vec<(usize, usize)> = cells.iter().copied().filter(|&(r, c)| self.board[r][c] != 0).collect();
 
+        for _ in 0..8 {
+            let kind = if alive.is_empty() { 1 } else { rng.gen_range(0, 3) };
+            match kind {
+                // nudge
+                0 => {
+                    let (r, c) = alive[rng.gen_range(0, alive.len())];
+                    let (dr, dc) = (rng.gen_range(-1, 2), rng.gen_range(-1, 2));
+                    let (tr, tc) = (r as isize + dr, c as isize + dc);
+                    if tr < 0 || tc < 0 || self.cell(tr as usize, tc as usize) != Some(0) {
+                        continue;
+                    }
+                    self.board[r][c] = 0;
+                    self.board[tr as usize][tc as usize] = 1;
+                }
+                // add
+                1 => {
+                    let (r, c) = cells[rng.gen_range(0, cells.len())];
+                    if self.board[r][c] != 0 {
+                        continue;
+                    }
+                    self.board[r][c] = 1;
+                }
+                // remove
+                _ => {
+                    let (r, c) = alive[rng.gen_range(0, alive.len())];
+                    self.board[r][c] = 0;
+                }
+            }
+            return;
+        }
+    }
+
+    /// returns a child of this configuration and `other`, cut in two along a random row or
+    /// column with the cells before the cut from this one and the rest from `other`
+    ///
+    /// Both parents are lined up by their top left corners in a box big enough for either, and
+    /// the child keeps this configuration's rule. The same `seed` always makes the same child.
+    pub fn crossover(&self, other: &Configuration, seed: u64) -> Configuration {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let width = |c: &Configuration| c.board.iter().map(|r| r.len()).max().unwrap_or(0);
+        let rows = self.board.len().max(other.board.len());
+        let cols = width(self).max(width(other));
+
+        let by_row = rng.gen::<bool>();
+        let cut = rng.gen_range(0, if by_row { rows } else { cols } + 1);
+        let board = (0..rows)
+            .map(|r| {
+                (0..cols)
+                    .map(|c| {
+                        let parent = if (if by_row { r } else { c }) < cut { self } else { other };
+                        parent.cell(r, c).unwrap_or(0)
+                    })
+                    .collect()
+            })
+            .collect();
+        Configuration {
+            rows,
+            cols,
+            rule: self.rule.clone(),
+            origin: self.origin,
+            rule_conflict: self.rule_conflict.clone(),
+            board,
+        }
+    }
+
+    /// Applies the configuration with its top left corner at (`row`, `col`) on the board.
+    ///
+    /// Unlike `apply_config`, the board isn't cleared first so cells outside of the
+    /// pattern are left alone.
+    pub fn apply_config_at(&self, board: &mut Board, row: usize, col: usize) -> result::Result<(), ApplyError> {
+        self.check_fits(board, row, col)?;
+        self.stamp(board, row, col);
+        Ok(())
+    }
+
+    pub(crate) fn check_fits(&self, board: &Board, row: usize, col: usize) -> result::Result<(), ApplyError> {
+        if row + self.board.len() > board.rows {
+            return Err(ApplyError::TooManyRows { required: row + self.board.len(), available: board.rows });
+        }
+        let widest = self.board.iter().map(|r| r.len()).max().unwrap_or(0);
+        if col + widest > board.cols {
+            return Err(ApplyError::TooManyCols { required: col + widest, available: board.cols });
+        }
         Ok(())
     }
+
+    fn stamp(&self, board: &mut Board, row: usize, col: usize) {
+        for (x, r) in self.board.iter().enumerate() {
+            for (y, val) in r.iter().enumerate() {
+                if *val != 0 {
+                    board.grid[row + x][col + y].is_alive = true;
+                }
+            }
+        }
+    }
 }
 
+/// Reads the whole file at `filepath` into a string
+fn read_file(filepath: &Path) -> String {
+    let display = filepath.display();
 
-/// Parse Run Length Encoded (RLE) config strings. Returns a parsed 2d vector of the board
+    // Open the path in read-only mode, returns `io::Result<File>`
+    let mut file = match File::open(filepath) {
+        Err(why) => panic!("couldn't open {}: {}", display, why),
+        Ok(file) => file,
+    };
+
+    // Read the file contents into a string, returns `io::Result<usize>`
+    let mut s = String::new();
+    if let Err(why) = file.read_to_string(&mut s) {
+        panic!("couldn't read {}: {}", display, why);
+    }
+    s
+}
+
+/// Largest bounding box, in cells, that `Configuration::parse` will build a dense Life 1.06 board for,
+/// and the largest board `Board::read_binary` will read
+pub const DEFAULT_MAX_DENSE_CELLS: usize = 1 << 26;
+
+/// Parse Life 1.06 config strings into the (row, col) of every alive cell. Lines starting
+/// with `#` are skipped, every other line is an `x y` pair.
+pub(crate) fn parse_life106_string(s: &str) -> result::Result<Vec<(i64, i64)>, &'static str> {
+    let mut cells = Vec::new();
+    for line in s.lines().map(str::trim).filter(|l| !l.is_empty() && !l.starts_with('#')) {
+        let mut coords = line.split_whitespace().map(|n| n.parse::<i64>());
+        match (coords.next(), coords.next(), coords.next()) {
+            (Some(Ok(x)), Some(Ok(y)), None) => cells.push((y, x)),
+            _ => return Err("Invalid coordinates in Life 1.06 board!"),
+        }
+    }
+    Ok(cells)
+}
+
+/// returns the (top, left, rows, cols) of the box around the given cells, or `None` if there
+/// aren't any or the box is too big to index
+pub(crate) fn life106_bounds(cells: &[(i64, i64)]) -> Option<(i64, i64, usize, usize)> {
+    let top = cells.iter().map(|(r, _)| *r).min()?;
+    let left = cells.iter().map(|(_, c)| *c).min()?;
+    let bottom = cells.iter().map(|(r, _)| *r).max()?;
+    let right = cells.iter().map(|(_, c)| *c).max()?;
+    let rows = usize::try_from(bottom.checked_sub(top)?.checked_add(1)?).ok()?;
+    let cols = usize::try_from(right.checked_sub(left)?.checked_add(1)?).ok()?;
+    Some((top, left, rows, cols))
+}
+
+/// Parse plaintext (`.cells`) config strings. Returns a parsed 2d vector of the board
 /// described by the configuration given, if valid.
 ///
+/// For more info on the encoding, see [this link](http://www.conwaylife.com/wiki/Plaintext)
+fn parse_cells_string(cells_str: &str) -> result::Result<Vec<Vec<u8>>, &'static str> {
+    let mut board: Vec<Vec<u8>> = Vec::new();
+
+    for line in cells_str.lines() {
+        // skip comments
+        if line.starts_with('!') {
+            continue;
+        }
+
+        let mut row = Vec::new();
+        for ch in line.trim_end().chars() {
+            match ch {
+                '.' => row.push(0),
+                'O' | '*' => row.push(1),
+                _ => return Err("Invalid character in plaintext board!"),
+            }
+        }
+        board.push(row);
+    }
+
+    // pad all rows out to the widest one
+    let cols = board.iter().map(|row| row.len()).max().unwrap_or(0);
+    for row in board.iter_mut() {
+        row.resize(cols, 0);
+    }
+
+    Ok(board)
+}
+
+/// returns the (row, col) of every alive cell relative to the board's origin, row by row
+fn alive_from_origin(board: &Board) -> objects::Shape {
+    board.iter_cells_from_origin().filter(|(_, alive)| *alive).map(|(cell, _)| cell).collect()
+}
+
+/// Reads lines up to and including the header line of an RLE pattern
+///
+/// Lines are read one at a time, so the reader is left at the first line of cells.
+pub(crate) fn read_rle_header<R: BufRead>(r: R) -> result::Result<HeaderInfo, &'static str> {
+    let re_dimensions = Regex::new(RLE_DIMENSIONS).unwrap();
+    let re_life_type = Regex::new(RLE_RULE).unwrap();
+
+    let mut name = None;
+    let mut comment_rule = None;
+    for line in r.lines() {
+        let line = line.map_err(|_| "Couldn't read the RLE pattern!")?;
+        let line = line.trim();
+        let lower = line.to_ascii_lowercase();
+
+        if let Some(comment) = line.strip_prefix('#') {
+            if lower.starts_with("#n") && name.is_none() {
+                name = Some(comment[1..].trim().to_string());
+            } else if lower.starts_with("#r") && line.contains('/') {
+                comment_rule = Some(comment[1..].trim().to_string());
+            }
+            continue;
+        }
+        if line.is_empty() {
+            continue;
+        }
+
+        let dims = re_dimensions.captures(&lower).ok_or("Invalid board dimensions!")?;
+        let rule = re_life_type.captures(&lower).map(|c| {
+            // keep the original casing
+            let m = c.get(1).unwrap();
+            line[m.start()..m.end()].to_string()
+        });
+        // the header wins if the rule is given in both places, but the caller gets to hear
+        // about it if they disagree
+        let same = |header: &str, comment: &str| match (header.parse::<Rule>(), comment.parse::<Rule>()) {
+            (Ok(h), Ok(c)) => h == c,
+            _ => header.eq_ignore_ascii_case(comment),
+        };
+        let rule_conflict = match (&rule, &comment_rule) {
+            (Some(header), Some(comment)) if !same(header, comment) => comment_rule.clone(),
+            _ => None,
+        };
+        return Ok(HeaderInfo {
+            cols: dims[1].parse().map_err(|_| "Invalid board dimensions!")?,
+            rows: dims[2].parse().map_err(|_| "Invalid board dimensions!")?,
+            rule: rule.or(comment_rule),
+            rule_conflict,
+            name,
+        });
+    }
+    Err("Configuration does not describe a board!")
+}
+
+/// returns the (row, col) from a `#P x y` or `#R x y` line before an RLE pattern's header
+///
+/// `#r` lines with a rule in them, like `#r 23/3`, aren't positions.
+fn parse_rle_position(rle_str: &str) -> Option<(i64, i64)> {
+    for line in rle_str.lines().map(str::trim) {
+        if !line.starts_with('#') {
+            if line.is_empty() {
+                continue;
+            }
+            return None;
+        }
+        let lower = line.to_ascii_lowercase();
+        if !(lower.starts_with("#p") || (lower.starts_with("#r") && !line.contains('/'))) {
+            continue;
+        }
+        let mut coords = line[2..].split_whitespace().map(|n| n.parse::<i64>());
+        if let (Some(Ok(x)), Some(Ok(y)), None) = (coords.next(), coords.next(), coords.next()) {
+            return Some((y, x));
+        }
+    }
+    None
+}
+
+/// Parse Run Length Encoded (RLE) config strings. Returns a parsed 2d vector of the board
+/// described by the configuration given, if valid, along with the rule from the header if
+/// there was one.
+///
 /// For more info on the encoding, see [this link](http://www.conwaylife.com/wiki/Run_Length_Encoded)
-fn parse_rle_string(rle_str: &str) -> result::Result<Vec<Vec<u8>>, &'static str> {
-    static CONWAY_LIFE_TYPE: &str = "b3/s23";
+fn parse_rle_string(rle_str: &str) -> result::Result<(Vec<Vec<u8>>, Option<String>), &'static str> {
     static _DEAD_CELL: &str = "b";
     static ALIVE_CELL: &str = "o";
     static EOL: &str = "$";
@@ -112,13 +895,15 @@ fn parse_rle_string(rle_str: &str) -> result::Result<Vec<Vec<u8>>, &'static str>
     let mut board: Vec<Vec<u8>> = Vec::new();
     let mut x: usize = 0;
     let mut y: usize = 0;
+    let mut rule: Option<String> = None;
+    let mut comment_rule: Option<String> = None;
 
     // tracking vars for filling in the board as we go
     let mut sub_x: usize = 0;
     let mut sub_y: usize = 0;
 
-    let re_dimensions = Regex::new(r"\s*x\s*=\s*(\d+),\s*y\s*=\s*(\d+)").unwrap();
-    let re_life_type = Regex::new(r".*[type|rule]\s*=\s*([\w/]+)").unwrap();
+    let re_dimensions = Regex::new(RLE_DIMENSIONS).unwrap();
+    let re_life_type = Regex::new(RLE_RULE).unwrap();
     let re_board_desc = Regex::new(r"(\d*[bo$]|[!])").unwrap();
     let re_numbers = Regex::new(r"(\d+)").unwrap();
 
@@ -129,11 +914,20 @@ fn parse_rle_string(rle_str: &str) -> result::Result<Vec<Vec<u8>>, &'static str>
         if line.starts_with('#') && line[1..].starts_with('c') {
             continue;
         }
-        // TODO: handle other configuration elements
+
+        // older files give the rule on its own line. `#R` is also used for coordinates,
+        // so only take it as a rule if it looks like one
+        if line.starts_with("#r") && line.contains('/') {
+            comment_rule = Some(original_line[2..].trim().to_string());
+            continue;
+        }
+        // TODO: handle other configuration elements. Until then they're skipped
         //        N: name
         //        O: who/when made
         //      R/P: coordinates of where the top left corner of the pattern goes
-        //        r: the Game Of Life rules. Usually not used as it goes on the dimensions line
+        if line.starts_with('#') {
+            continue;
+        }
 
         // Handle dimensions and board type
         let mut matched_dim_or_type = false;
@@ -152,9 +946,9 @@ fn parse_rle_string(rle_str: &str) -> result::Result<Vec<Vec<u8>>, &'static str>
         if re_life_type.is_match(&line) {
             matched_dim_or_type = true;
             if let Some(captures) = re_life_type.captures(&line) {
-                if captures[1].to_ascii_lowercase() != *CONWAY_LIFE_TYPE {
-                    return Err("Specified life type not Conway! Cannot play config.");
-                }
+                // keep the original casing around for error messages
+                let m = captures.get(1).unwrap();
+                rule = Some(original_line[m.start()..m.end()].to_string());
             }
         }
 
@@ -186,6 +980,9 @@ fn parse_rle_string(rle_str: &str) -> result::Result<Vec<Vec<u8>>, &'static str>
                 }
 
                 if cap[0].find(ALIVE_CELL).is_some() {
+                    if sub_y >= y || sub_x + num_vals > x {
+                        return Err("Board description exceeds the given dimensions!");
+                    }
                     for _ in 0..num_vals {
                         board[sub_y][sub_x] = 1;
                         sub_x += 1;
@@ -200,35 +997,136 @@ fn parse_rle_string(rle_str: &str) -> result::Result<Vec<Vec<u8>>, &'static str>
         }
     }
 
-    Ok(board)
+    // the header wins if the rule is given in both places, see `read_rle_header` for how a
+    // conflict is reported
+    Ok((board, rule.or(comment_rule)))
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn rle_positions() {
+        let glider = "#N glider\n#P -4 2\nx = 3, y = 3, rule = B3/S23\nbo$2bo$3o!";
+        let c = Configuration::from_rle_str(glider).unwrap();
+        assert_eq!(c.origin, Some((2, -4)));
+        assert_eq!(Configuration::from_rle_str("#R 1 -1\n#r 23/3\nx = 1, y = 1\no!").unwrap().origin, Some((-1, 1)));
+        assert_eq!(Configuration::from_rle_str("x = 1, y = 1\no!\n#P 3 3").unwrap().origin, None);
+        assert_eq!(Configuration::from_rle_str("#P 3\nx = 1, y = 1\no!").unwrap().origin, None);
+
+        // placed from the middle of the board
+        let mut b = Board::new(10, 10);
+        c.apply_config(&mut b).unwrap();
+        assert!(b.grid[7][2].is_alive && b.grid[9][1].is_alive);
+        assert_eq!(b.get_num_alive_cells(), 5);
+
+        // and pulled back on if it would fall off
+        let mut b = Board::new(4, 4);
+        c.apply_config(&mut b).unwrap();
+        assert!(b.grid[1][1].is_alive && b.grid[3][0].is_alive);
 
-//     #[test]
-//     fn rle_load_glider_no_type() {
-//         let glider_rle = "#C This is a glider.
-// x = 3, y = 3
-// bo$2bo$3o!";
-
-//         let expected_vec: Vec<Vec<u8>> = vec![
-//             vec![0, 1, 0],
-//             vec![0, 0, 1],
-//             vec![1, 1, 1],
-//         ];
-
-//         match parse_rle_string(&glider_rle) {
-//             Ok(board) => assert!(
-//                 expected_vec == board,
-//                 "Board did not match! expected: {:?}, got: {:?}",
-//                 expected_vec,
-//                 board
-//             ),
-//             Err(error) => assert!(false, error),
-//         }
-//     }
+        // an explicit position wins
+        let mut b = Board::new(10, 10);
+        c.apply_config_at(&mut b, 0, 0).unwrap();
+        assert!(b.grid[0][1].is_alive);
+    }
+
+    #[test]
+    fn crossovers() {
+        let full = Configuration::from_cells_str("OOOO\nOOOO\nOOOO").unwrap();
+        let empty = Configuration::from_cells_str("..\n..").unwrap();
+        // what a child cut before row or column `cut` looks like. The empty parent is padded
+        // with dead cells, so everything past the cut is dead
+        let split = |by_row: bool, cut: usize| -> Vec<Vec<u8>> {
+            (0..3)
+                .map(|r| (0..4).map(|c| ((if by_row { r } else { c }) < cut) as u8).collect())
+                .collect()
+        };
+
+        let mut mixed = false;
+        for seed in 0..20 {
+            let child = full.crossover(&empty, seed);
+            assert_eq!((child.rows, child.cols), (3, 4));
+            assert_eq!(child.as_grid(), full.crossover(&empty, seed).as_grid());
+            assert!((0..=3).any(|cut| child.as_grid() == &split(true, cut))
+                || (0..=4).any(|cut| child.as_grid() == &split(false, cut)));
+            mixed |= child.as_grid()[0][0] != child.as_grid()[1][1];
+        }
+        assert!(mixed);
+        assert_eq!(full.crossover(&empty, 0).rule, full.rule);
+    }
+
+    #[test]
+    fn jittering() {
+        let glider = Configuration::builtin("glider").unwrap();
+        let variants = glider.jittered_variants(30, 9);
+        assert_eq!(variants.len(), 30);
+        for v in &variants {
+            assert_eq!((v.rows, v.cols), (3, 3));
+            let changed = v.as_grid().iter().flatten().zip(glider.as_grid().iter().flatten()).filter(|(a, b)| a != b).count();
+            let alive = v.as_grid().iter().flatten().filter(|c| **c != 0).count();
+            // a nudge changes two cells and keeps five alive, an addition or removal changes one
+            assert!((changed == 2 && alive == 5) || (changed == 1 && (alive == 4 || alive == 6)));
+        }
+        assert!(variants.iter().any(|v| v.as_grid() != variants[0].as_grid()));
+
+        let again = glider.jittered_variants(30, 9);
+        assert!(variants.iter().zip(&again).all(|(a, b)| a.as_grid() == b.as_grid()));
+
+        // with nothing alive, the only change is bringing a cell to life
+        let empty = Configuration::from_cells_str("..\n..").unwrap();
+        for v in empty.jittered_variants(5, 1) {
+            assert_eq!(v.as_grid().iter().flatten().filter(|c| **c != 0).count(), 1);
+        }
+    }
+
+    #[test]
+    fn mutations() {
+        let glider = Configuration::builtin("glider").unwrap();
+
+        let mut same = Configuration::builtin("glider").unwrap();
+        same.mutate(0., 7);
+        assert_eq!(same.as_grid(), glider.as_grid());
+
+        let mut inverted = Configuration::builtin("glider").unwrap();
+        inverted.mutate(1., 7);
+        assert_eq!(inverted.as_grid(), &vec![vec![1, 0, 1], vec![1, 1, 0], vec![0, 0, 0]]);
+
+        let mut a = Configuration::from_cells_str(&".".repeat(40)).unwrap();
+        let mut b = Configuration::from_cells_str(&".".repeat(40)).unwrap();
+        a.mutate(0.5, 42);
+        b.mutate(0.5, 42);
+        assert_eq!(a.as_grid(), b.as_grid());
+        assert!(a.as_grid()[0].contains(&1) && a.as_grid()[0].contains(&0));
+    }
+
+    #[test]
+    fn rle_load_glider_no_type() {
+        let glider_rle = "#C This is a glider.
+x = 3, y = 3
+bo$2bo$3o!";
+
+        let expected_vec: Vec<Vec<u8>> = vec![
+            vec![0, 1, 0],
+            vec![0, 0, 1],
+            vec![1, 1, 1],
+        ];
+
+        match parse_rle_string(glider_rle) {
+            Ok((board, rule)) => {
+                assert!(
+                    expected_vec == board,
+                    "Board did not match! expected: {:?}, got: {:?}",
+                    expected_vec,
+                    board
+                );
+                assert!(rule.is_none(), "Found a rule that wasn't there: {:?}", rule);
+            }
+            Err(error) => panic!("{}", error),
+        }
+    }
 
     #[test]
     fn rle_load_glider_type() {
@@ -242,14 +1140,14 @@ bo$2bo$3o!";
             vec![1, 1, 1],
         ];
 
-        match parse_rle_string(&glider_rle) {
-            Ok(board) => assert!(
+        match parse_rle_string(glider_rle) {
+            Ok((board, _)) => assert!(
                 expected_vec == board,
                 "Board did not match! expected: {:?}, got: {:?}",
                 expected_vec,
                 board
             ),
-            Err(error) => assert!(false, error),
+            Err(error) => panic!("{}", error),
         }
     }
 
@@ -259,21 +1157,259 @@ bo$2bo$3o!";
 x = x, y = 3
 bo$2bo$3o!";
 
-        match parse_rle_string(&glider_rle) {
-            Ok(_) => assert!(false, "Board should not have initialized properly!"),
+        match parse_rle_string(glider_rle) {
+            Ok(_) => panic!("Board should not have initialized properly!"),
             Err(err) => eprintln!("Errored out properly: {}", err),
         }
     }
 
     #[test]
-    fn rle_load_invalid_type() {
+    fn apgcodes() {
+        let code = |cells: &str| Configuration::from_cells_str(cells).unwrap().apgcode();
+        assert_eq!(code("OO\nOO"), "xs4_33");
+        assert_eq!(code(".OO.\nO..O\n.OO."), "xs6_696");
+        assert_eq!(code("OO.\nO.O\n.O."), "xs5_253");
+        assert_eq!(code("OOO"), "xp2_7");
+        assert_eq!(code(".O\n..O\nOOO"), "xq4_153");
+        assert_eq!(code("..."), "xs0_0");
+        assert_eq!(code("OOOO"), "PATHOLOGICAL");
+    }
+
+    #[test]
+    fn orientations() {
+        let c = Configuration::from_cells_str("OO.\n..O").unwrap();
+        let grid = |o| c.oriented(o).as_grid().clone();
+        assert_eq!(grid(Orientation::Identity), vec![vec![1, 1, 0], vec![0, 0, 1]]);
+        assert_eq!(grid(Orientation::Rotate90), vec![vec![0, 1], vec![0, 1], vec![1, 0]]);
+        assert_eq!(grid(Orientation::Rotate180), vec![vec![1, 0, 0], vec![0, 1, 1]]);
+        assert_eq!(grid(Orientation::Rotate270), vec![vec![0, 1], vec![1, 0], vec![1, 0]]);
+        assert_eq!(grid(Orientation::FlipHorizontal), vec![vec![0, 1, 1], vec![1, 0, 0]]);
+        assert_eq!(grid(Orientation::FlipVertical), vec![vec![0, 0, 1], vec![1, 1, 0]]);
+        assert_eq!(grid(Orientation::Transpose), vec![vec![1, 0], vec![1, 0], vec![0, 1]]);
+        assert_eq!(grid(Orientation::AntiTranspose), vec![vec![1, 0], vec![0, 1], vec![0, 1]]);
+
+        let turned = c.oriented(Orientation::Rotate90);
+        assert_eq!((turned.rows, turned.cols), (3, 2));
+    }
+
+    #[test]
+    fn peek_headers() {
+        let header = |s: &str| read_rle_header(s.as_bytes());
+        assert_eq!(
+            header("#N Gosper glider gun\n#C the first gun found\nx = 36, y = 9, rule = B3/S23\n24bo$22bobo!"),
+            Ok(HeaderInfo { rows: 9, cols: 36, rule: Some(String::from("B3/S23")), rule_conflict: None, name: Some(String::from("Gosper glider gun")) })
+        );
+        assert_eq!(
+            header("#R 23/36\nx = 3, y = 1\n"),
+            Ok(HeaderInfo { rows: 1, cols: 3, rule: Some(String::from("23/36")), rule_conflict: None, name: None })
+        );
+        // the cells after the header don't matter
+        assert!(header("x = 3, y = 1\nthis isn't RLE").is_ok());
+        assert!(header("#N nothing\n").is_err());
+        assert!(header("3o!").is_err());
+
+        let path = std::env::temp_dir().join(format!("gameoflife-peek-{}.rle", std::process::id()));
+        std::fs::write(&path, "#N Glider\nx = 3, y = 3\nbo$2bo$3o!").unwrap();
+        let info = Configuration::peek_header(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!((info.rows, info.cols, info.name.as_deref()), (3, 3, Some("Glider")));
+        assert!(Configuration::peek_header(&path).is_err());
+    }
+
+    #[test]
+    fn required_bounds() {
+        let bounds = |cells: &str, max_gen| Configuration::from_cells_str(cells).unwrap().required_bounds(max_gen);
+        assert_eq!(bounds("OO\nOO", 10), RequiredBounds { rows: 2, cols: 2, stable: true });
+        // a blinker needs room for both of its phases
+        assert_eq!(bounds("OOO", 10), RequiredBounds { rows: 3, cols: 3, stable: true });
+        // a glider moves one cell diagonally every four generations
+        assert_eq!(bounds(".O\n..O\nOOO", 8), RequiredBounds { rows: 5, cols: 5, stable: false });
+        assert_eq!(bounds(".O\n..O\nOOO", 400), RequiredBounds { rows: 103, cols: 103, stable: false });
+        // the board only grows as far as the pattern does, however long the run could be
+        assert_eq!(bounds("OO\nOO", 1_000_000), RequiredBounds { rows: 2, cols: 2, stable: true });
+        assert_eq!(bounds("O", 10), RequiredBounds { rows: 1, cols: 1, stable: true });
+        assert_eq!(bounds("...", 10), RequiredBounds { rows: 0, cols: 0, stable: true });
+    }
+
+    #[test]
+    fn generations_rle() {
+        let c = Configuration::from_rle_str("x = 3, y = 1, rule = 345/2/4\n3o!").unwrap();
+        assert_eq!(c.rule().unwrap(), Rule::generations(&[2], &[3, 4, 5], 4).unwrap());
+        let c = Configuration::from_rle_str("#r 345/2/4\nx = 3, y = 1\n3o!").unwrap();
+        assert_eq!(c.rule().unwrap().states(), 4);
+    }
+
+    #[test]
+    fn periods() {
+        let period = |cells: &str, max_gen| Configuration::from_cells_str(cells).unwrap().period(max_gen);
+        assert_eq!(period("OO\nOO", 10), Some(1));
+        assert_eq!(period("OOO", 10), Some(2));
+        assert_eq!(period(".O\n..O\nOOO", 10), Some(4));
+        assert_eq!(period(".O\n..O\nOOO", 3), None);
+        assert_eq!(period("...", 10), Some(1));
+        assert_eq!(period("OOOO", 20), None);
+        assert_eq!(period("OOO", 1_000_000), Some(2));
+    }
+
+    #[test]
+    fn exact_periods() {
+        let is_period = |cells: &str, p| Configuration::from_cells_str(cells).unwrap().is_oscillator_of_period(p);
+        assert!(is_period("OO\nOO", 1));
+        assert!(!is_period("OO\nOO", 2));
+        assert!(is_period("OOO", 2));
+        // a blinker is back after 4 generations too, but that's not its period
+        assert!(!is_period("OOO", 4));
+        assert!(!is_period("OOO", 3));
+        assert!(!is_period("OOO", 0));
+        // gliders are the same shape after 4 generations, but somewhere else
+        assert!(!is_period(".O\n..O\nOOO", 4));
+        assert!(!is_period("...", 1));
+        // three cells of a block become a block, which never goes back
+        assert!(!is_period("OO\nO.", 1));
+
+        // the pattern's own rule is used
+        let mut block = Configuration::from_cells_str("OO\nOO").unwrap();
+        block.rule = Some(String::from("B3/S"));
+        assert!(!block.is_oscillator_of_period(1));
+    }
+
+    #[test]
+    fn cell_accessors() {
+        let mut c = Configuration::from_cells_str(".O\n..O\nOOO").unwrap();
+        assert_eq!(c.cell(0, 1), Some(1));
+        assert_eq!(c.cell(0, 0), Some(0));
+        assert_eq!(c.cell(3, 0), None);
+
+        c.set_cell(0, 1, 0).unwrap();
+        c.set_cell(0, 0, 1).unwrap();
+        assert!(c.set_cell(0, 3, 1).is_err());
+        assert_eq!(c.as_grid()[0], vec![1, 0, 0]);
+    }
+
+    #[test]
+    fn apply_too_big() {
+        let c = Configuration::from_cells_str(".O\n..O\nOOO").unwrap();
+        let mut b = Board::new(2, 5);
+        assert_eq!(c.apply_config(&mut b), Err(ApplyError::TooManyRows { required: 3, available: 2 }));
+
+        let mut b = Board::new(5, 4);
+        assert_eq!(c.apply_config_at(&mut b, 1, 2), Err(ApplyError::TooManyCols { required: 5, available: 4 }));
+        assert!(c.apply_config_at(&mut b, 1, 1).is_ok());
+    }
+
+    #[test]
+    fn rle_writes_glider() {
+        let mut c = Configuration::from_cells_str(".O\n..O\nOOO").unwrap();
+        c.rule = Some(String::from("B36/S23"));
+        assert_eq!(c.to_rle_string(), "x = 3, y = 3, rule = B36/S23\nbo$2bo$3o!\n");
+    }
+
+    proptest! {
+        // failing cases are saved under proptest-regressions/ and rerun first, so they stay reproducible
+        #[test]
+        fn rle_round_trip(board in (1usize..12, 1usize..100).prop_flat_map(|(rows, cols)| {
+            prop::collection::vec(prop::collection::vec(prop::sample::select(vec![0u8, 1]), cols), rows)
+        })) {
+            let conf = Configuration::from_vec(board.clone()).unwrap();
+            let rle = conf.to_rle_string();
+            prop_assert!(rle.lines().all(|l| l.len() <= RLE_LINE_LENGTH), "{}", rle);
+
+            let (parsed, rule) = parse_rle_string(&rle).unwrap();
+            prop_assert_eq!(parsed, board);
+            prop_assert_eq!(rule, None);
+        }
+    }
+
+    #[test]
+    fn builtin_patterns() {
+        for name in Configuration::builtin_names() {
+            let c = Configuration::builtin(name).unwrap();
+            assert!(c.as_grid().iter().flatten().any(|v| *v != 0), "{} is empty", name);
+        }
+
+        let gun = Configuration::builtin("Glider-Gun").unwrap();
+        assert_eq!((gun.rows, gun.cols), (9, 36));
+        assert_eq!(gun.as_grid().iter().flatten().filter(|v| **v != 0).count(), 36);
+        assert!(Configuration::builtin("spaceship").is_none());
+    }
+
+    #[test]
+    fn record_and_load_sequence() {
+        let mut b = Board::new(6, 6);
+        Configuration::builtin("glider").unwrap().apply_config(&mut b).unwrap();
+        let path = std::env::temp_dir().join(format!("gameoflife-sequence-{}.rle", std::process::id()));
+        crate::app::record_rle(&b, 4, &path).unwrap();
+
+        let frames = Configuration::load_sequence(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(frames.len(), 5);
+        match Configuration::load_sequence(&path) {
+            Err(why) => assert!(why.starts_with("couldn't read"), "{}", why),
+            Ok(_) => panic!("loaded a missing file"),
+        }
+        for (n, frame) in frames.iter().enumerate() {
+            assert_eq!(frame.as_grid(), b.extract_region(0, 0, 6, 6).unwrap().as_grid(), "frame {}", n);
+            b.update();
+        }
+
+        assert!(Configuration::parse_sequence("x = 2, y = 1\n2o!\nx = 2, y = 1\n").is_err());
+    }
+
+    #[test]
+    fn life106_glider() {
+        let glider = "#Life 1.06
+0 -1
+1 0
+-1 1
+0 1
+1 1";
+        let c = Configuration::from_life106_str(glider, DEFAULT_MAX_DENSE_CELLS).unwrap();
+        assert_eq!((c.rows, c.cols), (3, 3));
+        assert_eq!(c.as_grid(), &vec![vec![0, 1, 0], vec![0, 0, 1], vec![1, 1, 1]]);
+
+        assert!(Configuration::from_life106_str("0 0\n1", DEFAULT_MAX_DENSE_CELLS).is_err());
+        assert!(Configuration::from_life106_str("#Life 1.06\n", DEFAULT_MAX_DENSE_CELLS).is_err());
+    }
+
+    #[test]
+    fn life106_too_large() {
+        let far_apart = "#Life 1.06\n-1000000000 0\n1000000000 5000000";
+        assert!(Configuration::from_life106_str(far_apart, DEFAULT_MAX_DENSE_CELLS).is_err());
+        assert!(Configuration::from_life106_str("0 0\n9 9", 99).is_err());
+        assert!(Configuration::from_life106_str("0 0\n9 9", 100).is_ok());
+    }
+
+    #[test]
+    fn zero_size_config() {
+        assert!(Configuration::from_rle_str("x = 0, y = 0\n!").is_err());
+        assert!(Configuration::from_rle_str("x = 3, y = 0\n").is_err());
+        assert!(Configuration::from_cells_str("!Name: nothing\n").is_err());
+    }
+
+    #[test]
+    fn rle_load_other_type() {
         let glider_rle = "#C This is a glider.
 x = 3, y = 3, type = B36/S23
 bo$2bo$3o!";
 
-        match parse_rle_string(&glider_rle) {
-            Ok(_) => assert!(false, "Board should not have initialized properly!"),
-            Err(err) => eprintln!("Errored out properly: {}", err),
+        let (board, rule) = parse_rle_string(glider_rle).unwrap();
+        let mut c = Configuration::from_vec(board).unwrap();
+        c.rule = rule;
+        assert_eq!(c.rule().unwrap().to_string(), "B36/S23");
+    }
+
+    #[test]
+    fn rle_load_invalid_type() {
+        let glider_rle = "#C This is a glider.
+x = 3, y = 3, rule = B3/S23/Hex
+bo$2bo$3o!";
+
+        let (board, rule) = parse_rle_string(glider_rle).unwrap();
+        let mut c = Configuration::from_vec(board).unwrap();
+        c.rule = rule;
+        match c.rule() {
+            Ok(_) => panic!("Rule should not have parsed properly!"),
+            Err(err) => assert!(err.contains("B3/S23/Hex"), "Error didn't name the rule: {}", err),
         }
     }
 
@@ -283,9 +1419,95 @@ bo$2bo$3o!";
 x = 3, y = 3, type = B3/S23
 bo$2bo$3o!";
 
-        match parse_rle_string(&glider_rle) {
+        match parse_rle_string(glider_rle) {
             Ok(_) => println!("Parsed properly!"),
-            Err(err) => assert!(false, "Errored out incorrectly: {}", err),
+            Err(err) => panic!("Errored out incorrectly: {}", err),
+        }
+    }
+
+    #[test]
+    fn rle_load_oversized_body() {
+        let glider_rle = "x = 3, y = 3
+bo$2bo$4o!";
+
+        match parse_rle_string(glider_rle) {
+            Ok(_) => panic!("Board should not have initialized properly!"),
+            Err(err) => eprintln!("Errored out properly: {}", err),
+        }
+    }
+
+    #[test]
+    fn empty_config() {
+        match parse_rle_string("") {
+            Ok((board, _)) => assert!(Configuration::from_vec(board).is_err()),
+            Err(err) => eprintln!("Errored out properly: {}", err),
         }
     }
+
+    #[test]
+    fn cells_load_glider() {
+        let glider_cells = "!Name: Glider
+.O
+..O
+OOO";
+
+        let expected_vec: Vec<Vec<u8>> = vec![
+            vec![0, 1, 0],
+            vec![0, 0, 1],
+            vec![1, 1, 1],
+        ];
+
+        match parse_cells_string(glider_cells) {
+            Ok(board) => assert!(
+                expected_vec == board,
+                "Board did not match! expected: {:?}, got: {:?}",
+                expected_vec,
+                board
+            ),
+            Err(error) => panic!("{}", error),
+        }
+    }
+
+    #[test]
+    fn infer_format() {
+        assert_eq!(Format::infer(r#"{"board": [[1]]}"#), Some(Format::Json));
+        assert_eq!(Format::infer("#C glider\nx = 3, y = 3\nbo$2bo$3o!"), Some(Format::Rle));
+        assert_eq!(Format::infer("!Name: Glider\n.O\n..O\nOOO"), Some(Format::Cells));
+        assert_eq!(Format::infer("#Life 1.06\n0 0"), Some(Format::Life106));
+        assert_eq!(Format::infer("hello there"), None);
+        assert_eq!(Format::infer(""), None);
+    }
+
+    #[test]
+    fn rle_load_comment_rule() {
+        let glider_rle = "#C This is a glider.
+#r b36/s23
+x = 3, y = 3
+bo$2bo$3o!";
+
+        let (board, rule) = parse_rle_string(glider_rle).unwrap();
+        assert_eq!(board[2], vec![1, 1, 1]);
+        assert_eq!(rule.unwrap().parse::<Rule>().unwrap().to_string(), "B36/S23");
+    }
+
+    #[test]
+    fn rle_load_header_rule_wins() {
+        let glider_rle = "#r B36/S23
+x = 3, y = 3, rule = B3/S23
+bo$2bo$3o!";
+
+        let (_, rule) = parse_rle_string(glider_rle).unwrap();
+        assert!(rule.unwrap().parse::<Rule>().unwrap().is_conway());
+
+        // the conflict is reported, but not when the two only differ in notation
+        let c = Configuration::from_rle_str(glider_rle).unwrap();
+        assert!(c.rule().unwrap().is_conway());
+        assert_eq!(c.rule_conflict.as_deref(), Some("B36/S23"));
+        let header = read_rle_header(glider_rle.as_bytes()).unwrap();
+        assert_eq!(header.rule_conflict.as_deref(), Some("B36/S23"));
+        let same = Configuration::from_rle_str("#r 23/3\nx = 3, y = 1, rule = B3/S23\n3o!").unwrap();
+        assert_eq!(same.rule_conflict, None);
+        let comment_only = Configuration::from_rle_str("#r 23/3\nx = 3, y = 1\n3o!").unwrap();
+        assert_eq!(comment_only.rule_conflict, None);
+    }
 }
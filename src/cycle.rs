@@ -0,0 +1,171 @@
+/// Spotting when a board starts repeating itself
+///
+/// `CycleDetector` is what `Board::distinct_states_visited` counts with, and what the
+/// headless analyses use to stop once a pattern has settled into a cycle.
+///
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+
+use crate::board::Board;
+use crate::topology::EdgeMode;
+
+/// Remembers the states a board has been in and the generation each was first seen in
+///
+/// States are told apart by where the alive cells are relative to the board's origin (see
+/// `Board::set_origin`), so a board that's been compacted or grown around its cells is still
+/// in the same state. On a torus, positions wrap around the edges too, so a board moved with
+/// `Board::torus_shift` is still in the same state. Under a `Rule::History` rule, the part
+/// of each cell's history the rule looks at counts too. Only a 64 bit hash of each state is
+/// kept.
+#[derive(Clone, Debug, Default)]
+pub struct CycleDetector {
+    seen: HashMap<u64, usize>,
+}
+
+impl CycleDetector {
+    pub fn new() -> CycleDetector {
+        CycleDetector::default()
+    }
+
+    /// Records the board's current state
+    ///
+    /// returns the generation the state was first seen in if it has been seen before, or
+    /// `None` if it's new.
+    pub fn observe(&mut self, board: &Board) -> Option<usize> {
+        match self.seen.entry(state_hash(board)) {
+            Entry::Occupied(first) => Some(*first.get()),
+            Entry::Vacant(new) => {
+                new.insert(board.get_generation());
+                None
+            }
+        }
+    }
+
+    /// true if the board's current state has been seen before
+    pub fn contains(&self, board: &Board) -> bool {
+        self.seen.contains_key(&state_hash(board))
+    }
+
+    /// returns the number of different states seen
+    pub fn len(&self) -> usize {
+        self.seen.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.seen.is_empty()
+    }
+
+    /// Forgets every state seen so far
+    pub fn clear(&mut self) {
+        self.seen.clear();
+    }
+}
+
+/// returns a hash of the alive cells' positions relative to the board's origin, and of any
+/// history the rule looks at
+///
+/// Each cell is hashed on its own with 64 bit FNV-1a and the results are added up, so the
+/// order the cells are visited in doesn't matter. FNV-1a's last steps are close enough to
+/// linear that nearby cells' hashes could cancel out in the sum, so each one is run through
+/// MurmurHash3's finalizer first.
+fn state_hash(board: &Board) -> u64 {
+    let fnv = |values: &[i64]| {
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+        for byte in values.iter().flat_map(|v| v.to_le_bytes()) {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+        hash ^= hash >> 33;
+        hash = hash.wrapping_mul(0xff51_afd7_ed55_8ccd);
+        hash ^= hash >> 33;
+        hash = hash.wrapping_mul(0xc4ce_b9fe_1a85_ec53);
+        hash ^ (hash >> 33)
+    };
+
+    let (origin_r, origin_c) = (board.origin.0 as i64, board.origin.1 as i64);
+    let relative = |r: usize, c: usize| match board.edges {
+        EdgeMode::Torus => (
+            (r as i64 - origin_r).rem_euclid(board.rows as i64),
+            (c as i64 - origin_c).rem_euclid(board.cols as i64),
+        ),
+        _ => (r as i64 - origin_r, c as i64 - origin_c),
+    };
+
+    let mut alive = 0;
+    let mut sum: u64 = 0;
+    for ((r, c), _) in board.iter_cells().filter(|(_, alive)| **alive) {
+        alive += 1;
+        let (r, c) = relative(r, c);
+        sum = sum.wrapping_add(fnv(&[r, c]));
+    }
+    for (r, row) in board.history.iter().enumerate() {
+        for (c, history) in row.iter().enumerate() {
+            let history = history & board.rule.history_mask();
+            if history != 0 {
+                let (r, c) = relative(r, c);
+                sum = sum.wrapping_add(fnv(&[r, c, i64::from(history)]));
+            }
+        }
+    }
+    sum ^ fnv(&[alive])
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::config::Configuration;
+
+    #[test]
+    fn test_observe() {
+        let mut b = Board::from_config(&Configuration::builtin("blinker").unwrap(), 1);
+        b.set_origin(1, 2);
+        let mut seen = CycleDetector::new();
+        assert_eq!(seen.observe(&b), None);
+        b.update();
+        assert_eq!(seen.observe(&b), None);
+        b.update();
+        assert_eq!(seen.observe(&b), Some(0));
+        assert_eq!(seen.len(), 2);
+
+        // the same cells around the same origin are the same state on any size of board
+        let mut compacted = b.clone();
+        compacted.compact(0);
+        assert!(seen.contains(&b));
+        seen.clear();
+        seen.observe(&compacted);
+        assert!(seen.contains(&b));
+
+        // but not around a different one
+        b.set_origin(0, 2);
+        assert!(!seen.contains(&b));
+    }
+
+    #[test]
+    fn test_observe_torus_shift() {
+        let mut b = Board::new(6, 6);
+        b.edges = EdgeMode::Torus;
+        b.stamp_rle("3o!", 0, 3).unwrap();
+        let mut seen = CycleDetector::new();
+        seen.observe(&b);
+        for (dr, dc) in &[(1, 2), (-3, 5), (4, 0)] {
+            b.torus_shift(*dr, *dc).unwrap();
+            assert!(seen.contains(&b), "shifted by ({}, {})", dr, dc);
+        }
+    }
+
+    #[test]
+    fn test_observe_history() {
+        // the same cells with a different past can play out differently under a history rule
+        let mut b = Board::new(5, 5);
+        b.rule = "B3/S23/H2".parse().unwrap();
+        b.stamp_rle("2o$2o!", 1, 1).unwrap();
+        let mut seen = CycleDetector::new();
+        assert_eq!(seen.observe(&b), None);
+        b.update();
+        assert_eq!(seen.observe(&b), None);
+        b.update();
+        assert_eq!(seen.observe(&b), None);
+        b.update();
+        assert_eq!(seen.observe(&b), Some(2));
+    }
+}
@@ -0,0 +1,207 @@
+use crate::rule::Rule;
+use crate::topology::{EdgeMode, Topology};
+
+/// A board that lives in a buffer the caller provides
+///
+/// This is the stepping core of the game with no dependence on `std` or the heap, for
+/// microcontrollers and the like. `Board` is the full featured version for everywhere else.
+pub struct Grid<'a> {
+    /// The rule used to compute the next generation. Defaults to Conway's B3/S23
+    pub rule: Rule,
+
+    /// Which cells count as neighbors. Defaults to the square Moore neighborhood
+    pub topology: Topology,
+
+    /// What cells see past the edges of the board. Defaults to dead cells
+    pub edges: EdgeMode,
+
+    rows: usize,
+    cols: usize,
+
+    /// Number of times the grid has been updated
+    generation: usize,
+
+    /// Two generations of cell states in row major order, one after the other. `current`
+    /// says which half is the one being shown, the other is written to by `update`
+    cells: &'a mut [u8],
+    current: usize,
+}
+
+impl<'a> Grid<'a> {
+    /// returns the number of bytes of buffer a `rows` by `cols` grid needs
+    pub const fn buffer_len(rows: usize, cols: usize) -> usize {
+        2 * rows * cols
+    }
+
+    /// Makes a grid of the given dimensions with all cells dead, kept in `buf`
+    ///
+    /// `buf` has to be at least `Grid::buffer_len(rows, cols)` bytes long.
+    pub fn new(rows: usize, cols: usize, buf: &'a mut [u8]) -> Result<Grid<'a>, &'static str> {
+        let len = rows.checked_mul(cols).and_then(|n| n.checked_mul(2)).ok_or("Grid is too big!")?;
+        if buf.len() < len {
+            return Err("Buffer is too small for the grid!");
+        }
+
+        let cells = &mut buf[..len];
+        for cell in cells.iter_mut() {
+            *cell = 0;
+        }
+        Ok(Grid {
+            rule: Rule::conway(),
+            topology: Topology::Moore,
+            edges: EdgeMode::Dead,
+            rows,
+            cols,
+            generation: 0,
+            cells,
+            current: 0,
+        })
+    }
+
+    /// returns the number of rows in the grid.
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// returns the number of columns in the grid.
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// returns the number of generations the grid has been updated for.
+    pub fn get_generation(&self) -> usize {
+        self.generation
+    }
+
+    /// returns the state of the cell at (`row`, `col`): 0 for dead, 1 for alive and 2 and up
+    /// for each generation of dying, or `None` if it's off the grid
+    pub fn state(&self, row: usize, col: usize) -> Option<u8> {
+        if row >= self.rows || col >= self.cols {
+            return None;
+        }
+        Some(self.cells[self.current + row * self.cols + col])
+    }
+
+    /// true if the cell at (`row`, `col`) is alive. Cells off the grid are dead
+    pub fn is_alive(&self, row: usize, col: usize) -> bool {
+        self.state(row, col) == Some(1)
+    }
+
+    /// Sets the cell at (`row`, `col`) alive or dead
+    pub fn set_alive(&mut self, row: usize, col: usize, alive: bool) -> Result<(), &'static str> {
+        if row >= self.rows || col >= self.cols {
+            return Err("Cell is outside of the grid!");
+        }
+        self.cells[self.current + row * self.cols + col] = alive as u8;
+        Ok(())
+    }
+
+    /// Sets all cells in the grid to dead
+    pub fn clear(&mut self) {
+        for cell in self.cells.iter_mut() {
+            *cell = 0;
+        }
+    }
+
+    /// returns the number of alive cells on the grid.
+    pub fn get_num_alive_cells(&self) -> usize {
+        let n = self.rows * self.cols;
+        self.cells[self.current..self.current + n].iter().filter(|s| **s == 1).count()
+    }
+
+    /// returns the number of alive neighbors of the cell at (`row`, `col`).
+    ///
+    /// Cells past the edges of the grid are handled according to `edges`.
+    pub fn live_neighbors(&self, row: usize, col: usize) -> u32 {
+        self.topology
+            .neighbor_offsets(row)
+            .iter()
+            .filter_map(|(dr, dc)| self.edges.resolve(row as isize + dr, col as isize + dc, self.rows, self.cols))
+            .filter(|(r, c)| self.is_alive(*r, *c))
+            .count() as u32
+    }
+
+    /// Advances the grid one generation
+    pub fn update(&mut self) {
+        let n = self.rows * self.cols;
+        let next = n - self.current;
+        for r in 0..self.rows {
+            for c in 0..self.cols {
+                let i = r * self.cols + c;
+                let state = self.cells[self.current + i];
+                self.cells[next + i] = self.rule.next_cell_state(state, self.live_neighbors(r, c));
+            }
+        }
+        self.current = next;
+        self.generation += 1;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn blinker() {
+        let mut buf = [0xff; Grid::buffer_len(5, 5)];
+        let mut g = Grid::new(5, 5, &mut buf).unwrap();
+        assert_eq!(g.get_num_alive_cells(), 0);
+        for c in 1..4 {
+            g.set_alive(2, c, true).unwrap();
+        }
+
+        g.update();
+        assert_eq!(g.get_generation(), 1);
+        assert_eq!(g.get_num_alive_cells(), 3);
+        assert!(g.is_alive(1, 2) && g.is_alive(2, 2) && g.is_alive(3, 2));
+        assert!(!g.is_alive(2, 1));
+
+        g.update();
+        assert!(g.is_alive(2, 1) && g.is_alive(2, 3));
+        assert!(!g.is_alive(1, 2));
+    }
+
+    #[test]
+    fn wraps_and_dies() {
+        // a glider on a tiny torus keeps its five cells
+        let mut buf = [0; 50];
+        let mut g = Grid::new(5, 5, &mut buf).unwrap();
+        g.edges = EdgeMode::Torus;
+        for &(r, c) in &[(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)] {
+            g.set_alive(r, c, true).unwrap();
+        }
+        for _ in 0..20 {
+            g.update();
+            assert_eq!(g.get_num_alive_cells(), 5);
+        }
+
+        g.clear();
+        assert_eq!(g.get_num_alive_cells(), 0);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn dying_states() {
+        let mut buf = [0; 32];
+        let mut g = Grid::new(4, 4, &mut buf).unwrap();
+        g.rule = "B2/S/C3".parse().unwrap();
+        g.set_alive(1, 1, true).unwrap();
+        g.set_alive(1, 2, true).unwrap();
+
+        g.update();
+        assert_eq!((g.state(1, 1), g.state(1, 2)), (Some(2), Some(2)));
+        assert_eq!(g.get_num_alive_cells(), 4);
+        g.update();
+        assert_eq!(g.state(1, 1), Some(0));
+        assert_eq!(g.state(4, 0), None);
+    }
+
+    #[test]
+    fn buffer_too_small() {
+        let mut buf = [0; 10];
+        assert!(Grid::new(3, 2, &mut buf).is_err());
+        assert!(Grid::new(2, 2, &mut buf).is_ok());
+        assert!(Grid::new(usize::MAX, 2, &mut buf).is_err());
+        assert!(Grid::new(2, 2, &mut buf).unwrap().set_alive(2, 0, true).is_err());
+    }
+}
@@ -1,3 +1,5 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
 /// Game of life crate that implements the game as well as the ability to load
 /// Game of Life configuration files from the community.
 ///
@@ -7,8 +9,26 @@
 ///   - Any live cell with more than three live neighbours dies, as if by overpopulation.
 ///   - Any dead cell with exactly three live neighbours becomes a live cell, as if by reproduction.
 ///
+/// Without the default `std` feature only `grid`, `rule` and `topology` are built, and
+/// the crate is `no_std`.
+///
+#[cfg(feature = "std")]
 pub mod board;
+#[cfg(feature = "std")]
 pub mod config;
+#[cfg(feature = "std")]
+pub mod cycle;
+pub mod grid;
+pub mod rule;
+pub mod topology;
+#[cfg(feature = "std")]
+pub mod sparse;
+#[cfg(feature = "std")]
 pub mod app;  // Only for the main.rs application...
 
-pub(crate) mod cell;
\ No newline at end of file
+#[cfg(feature = "std")]
+pub(crate) mod cell;
+#[cfg(feature = "std")]
+pub(crate) mod objects;
+#[cfg(feature = "std")]
+pub(crate) mod patterns;
\ No newline at end of file
@@ -1,11 +1,15 @@
 use std::time::Duration;
 use std::path::Path;
-use clap::{App, Arg, value_t};
+use std::process;
+use clap::{App, Arg, SubCommand, value_t};
 
 use gameoflife::app;
+use gameoflife::config::Configuration;
+use gameoflife::topology::Topology;
 
 
 fn main() {
+    let pattern_names = Configuration::builtin_names();
     let matches = App::new("game-of-life")
         .about("An implementation of Conway's Game of Life.")
         .arg(Arg::with_name("rows")
@@ -18,6 +22,17 @@ fn main() {
             .value_name("COLS")
             .help("Number of columns in the grid")
             .takes_value(true))
+        .arg(Arg::with_name("size")
+            .long("size")
+            .value_name("COLSxROWS")
+            .help("Size of the grid as columns x rows, e.g. 80x40")
+            .conflicts_with_all(&["rows", "cols"])
+            .validator(|s| parse_size(&s).map(|_| ()))
+            .takes_value(true))
+        .arg(Arg::with_name("fit")
+            .long("fit")
+            .help("Size the grid to fill the terminal")
+            .conflicts_with_all(&["rows", "cols", "size"]))
         .arg(Arg::with_name("rand-density")
             .short("p")
             .help("Probability that a spot is alive at the beginning - [0,1]")
@@ -26,19 +41,204 @@ fn main() {
             .long("rate")
             .help("Speed of the refresh cycles in miliseconds")
             .takes_value(true))
+        .arg(Arg::with_name("render-every")
+            .long("render-every")
+            .value_name("N")
+            .help("Number of generations to advance between each drawn frame")
+            .takes_value(true))
+        .arg(Arg::with_name("target-fps")
+            .long("target-fps")
+            .value_name("FPS")
+            .help("Draw this many frames per second, advancing extra generations per frame when drawing falls behind")
+            .conflicts_with_all(&["rate", "render-every"])
+            .takes_value(true))
+        .arg(Arg::with_name("loop")
+            .long("loop")
+            .help("Start over from the first generation once the board dies or stabilizes"))
+        .arg(Arg::with_name("exit-on")
+            .long("exit-on")
+            .help("When to stop on its own: once the board dies, once it stops changing, or never")
+            .possible_values(&["death", "stabilize", "never"])
+            .takes_value(true))
+        .arg(Arg::with_name("watch-region")
+            .long("watch-region")
+            .value_name("r0,c0,r1,c1")
+            .help("Also stop once no cells are alive in rows r0 to r1 and columns c0 to c1, ends not included")
+            .validator(|s| parse_region(&s).map(|_| ()))
+            .takes_value(true))
+        .arg(Arg::with_name("once")
+            .long("once")
+            .help("Print the first generation as plain text and exit"))
+        .arg(Arg::with_name("headless")
+            .long("headless")
+            .value_name("N")
+            .help("Run N generations without drawing, then print the last one as plain text")
+            .conflicts_with_all(&["once", "edit"])
+            .takes_value(true))
+        .arg(Arg::with_name("progress-every")
+            .long("progress-every")
+            .value_name("M")
+            .help("Print the generation and population to stderr every M generations of a headless run")
+            .requires("headless")
+            .takes_value(true))
+        .arg(Arg::with_name("step-timeout-ms")
+            .long("step-timeout-ms")
+            .value_name("MS")
+            .help("Stop a headless run after any generation that takes longer than MS milliseconds")
+            .requires("headless")
+            .takes_value(true))
+        .arg(Arg::with_name("append")
+            .long("append")
+            .help("Print each frame below the last as plain text instead of redrawing the screen")
+            .conflicts_with_all(&["once", "edit", "headless"]))
+        .arg(Arg::with_name("json-stream")
+            .long("json-stream")
+            .help("Print each generation as a line of JSON with its number and alive cells instead of drawing it")
+            .conflicts_with_all(&["once", "edit", "headless", "append"]))
+        .arg(Arg::with_name("edit")
+            .long("edit")
+            .help("Draw on the board before starting: arrows move, space toggles a cell and enter starts"))
         .arg(Arg::with_name("config-filepath")
             .long("config-filepath")
             .short("f")
             .help("Board configuration file. Supports custom JSON or standard RLE. See http://www.conwaylife.com/wiki/Run_Length_Encoded for more info.")
-            .takes_value(true)
-    ).get_matches();
+            .takes_value(true))
+        .arg(Arg::with_name("stdin")
+            .long("stdin")
+            .help("Read the board configuration from stdin")
+            .conflicts_with("config-filepath"))
+        .arg(Arg::with_name("pattern")
+            .long("pattern")
+            .help("Start from one of the built in patterns")
+            .possible_values(&pattern_names)
+            .conflicts_with_all(&["config-filepath", "stdin"])
+            .takes_value(true))
+        .arg(Arg::with_name("scene")
+            .long("scene")
+            .value_name("MANIFEST")
+            .help("Build the board from a JSON manifest of pattern files, positions and orientations")
+            .conflicts_with_all(&["config-filepath", "stdin", "pattern"])
+            .takes_value(true))
+        .arg(Arg::with_name("list-patterns")
+            .long("list-patterns")
+            .help("List the built in patterns and exit"))
+        .arg(Arg::with_name("format")
+            .long("format")
+            .help("Format of the configuration read from stdin. Guessed from its contents if not given")
+            .possible_values(&["json", "rle", "cells", "life106"])
+            .takes_value(true))
+        .arg(Arg::with_name("ghost")
+            .long("ghost")
+            .help("Show cells that died last generation as a faint dot"))
+        .arg(Arg::with_name("heatmap")
+            .long("heatmap")
+            .help("Color every cell by how many alive neighbors it has"))
+        .arg(Arg::with_name("show-counts")
+            .long("show-counts")
+            .help("Draw alive cells as their number of alive neighbors, and dead cells about to be born in green"))
+        .arg(Arg::with_name("trail")
+            .long("trail")
+            .help("Mark every cell that has ever been alive in a dim color"))
+        .arg(Arg::with_name("topology")
+            .long("topology")
+            .help("Which cells count as neighbors")
+            .possible_values(&["moore", "hex"])
+            .takes_value(true))
+        .arg(Arg::with_name("edges")
+            .long("edges")
+            .help("What cells see past the edges of the board")
+            .possible_values(&["dead", "torus", "mobius", "klein", "reflective"])
+            .takes_value(true))
+        .arg(Arg::with_name("wrap-margin")
+            .long("wrap-margin")
+            .value_name("N")
+            .help("Show N cells from across wrapped edges outside the border")
+            .takes_value(true))
+        .arg(Arg::with_name("force-conway")
+            .long("force-conway")
+            .help("Refuse to play configurations whose rule isn't Conway's B3/S23"))
+        .arg(Arg::with_name("no-status")
+            .long("no-status")
+            .help("Don't show the status line below the board"))
+        .subcommand(SubCommand::with_name("test-patterns")
+            .about("Loads and runs every pattern in a directory, reporting any failures")
+            .arg(Arg::with_name("dir")
+                .help("Directory of .rle, .cells and .json patterns")
+                .required(true))
+            .arg(Arg::with_name("generations")
+                .long("generations")
+                .short("g")
+                .help("Number of generations to run each pattern for")
+                .takes_value(true)))
+        .get_matches();
+
+    if let Some(sub) = matches.subcommand_matches("test-patterns") {
+        let dir = Path::new(sub.value_of("dir").unwrap());
+        let generations = value_t!(sub, "generations", usize).unwrap_or(100);
+        if !app::report_test_patterns(dir, generations) {
+            process::exit(1);
+        }
+        return;
+    }
+
+    if matches.is_present("list-patterns") {
+        for name in &pattern_names {
+            println!("{}", name);
+        }
+        return;
+    }
 
     // argument unwrapping / parsing
-    let rows = value_t!(matches, "rows", usize).unwrap_or(40);
-    let cols = value_t!(matches, "cols", usize).unwrap_or(80);
+    //
+    // the board size comes from, in order: -r/-c/--size, the pattern (see app::resolve_size),
+    // --fit and finally the 80x40 default
+    let mut rows = value_t!(matches, "rows", usize).unwrap_or(40);
+    let mut cols = value_t!(matches, "cols", usize).unwrap_or(80);
+    let mut size_source = app::SizeSource::Default;
+    if let Some(size) = matches.value_of("size") {
+        let (c, r) = parse_size(size).unwrap();
+        cols = c;
+        rows = r;
+    }
+    if matches.is_present("rows") || matches.is_present("cols") || matches.is_present("size") {
+        size_source = app::SizeSource::Flag;
+    }
+    let topology = matches.value_of("topology").map(|t| t.parse().unwrap()).unwrap_or_default();
+    let status = !matches.is_present("no-status");
+    if matches.is_present("fit") {
+        // keep the defaults if we can't tell how big the terminal is
+        if let Some((c, r)) = termion::terminal_size().ok().and_then(|size| fit_size(size, topology, status)) {
+            cols = c;
+            rows = r;
+            size_source = app::SizeSource::Fit;
+        }
+    }
     let dur = value_t!(matches, "rate", u64).unwrap_or(250);
+    let render_every = value_t!(matches, "render-every", usize).unwrap_or(1);
+    let target_fps = value_t!(matches, "target-fps", u32).ok().filter(|fps| *fps > 0);
+    let replay = matches.is_present("loop");
+    let once = matches.is_present("once");
+    let headless = value_t!(matches, "headless", usize).ok();
+    let progress_every = value_t!(matches, "progress-every", usize).ok();
+    let step_timeout = value_t!(matches, "step-timeout-ms", u64).ok().map(Duration::from_millis);
+    let append = matches.is_present("append");
+    let json_stream = matches.is_present("json-stream");
+    let edit = matches.is_present("edit");
+    let exit_on = matches.value_of("exit-on").map(|e| e.parse().unwrap()).unwrap_or(app::ExitOn::Death);
+    let watch_region = matches.value_of("watch-region").map(|r| parse_region(r).unwrap());
     let rand_prob = value_t!(matches, "rand-density", f32).unwrap_or(0.25);
     let path_str_opt = matches.value_of("config-filepath");
+    let stdin = matches.is_present("stdin");
+    let pattern = matches.value_of("pattern");
+    let scene = matches.value_of("scene").map(Path::new);
+    let format = matches.value_of("format").map(|f| f.parse().unwrap());
+    let ghost = matches.is_present("ghost");
+    let heatmap = matches.is_present("heatmap");
+    let trail = matches.is_present("trail");
+    let show_counts = matches.is_present("show-counts");
+    let edges = matches.value_of("edges").map(|e| e.parse().unwrap()).unwrap_or_default();
+    let wrap_margin = value_t!(matches, "wrap-margin", usize).unwrap_or(0);
+    let force_conway = matches.is_present("force-conway");
 
     // coax some types
     let duration = Duration::from_millis(dur);
@@ -49,5 +249,115 @@ fn main() {
         path = Some(p);
     }
 
-    app::app(rows, cols, Some(rand_prob), path, duration);
+    app::app(&app::Options {
+        rows,
+        cols,
+        size_source,
+        prob_density: Some(rand_prob),
+        init_filepath: path,
+        pattern,
+        scene,
+        stdin,
+        format,
+        update_rate: duration,
+        render_every,
+        target_fps,
+        replay,
+        exit_on,
+        watch_region,
+        once,
+        headless,
+        progress_every,
+        step_timeout,
+        append,
+        json_stream,
+        edit,
+        ghost,
+        heatmap,
+        trail,
+        show_counts,
+        wrap_margin,
+        topology,
+        edges,
+        force_conway,
+        status,
+    });
+}
+
+/// Parses a board size given as `COLSxROWS`, like `80x40`, into (cols, rows)
+fn parse_size(size: &str) -> Result<(usize, usize), String> {
+    let err = || format!("Invalid size '{}', expected COLSxROWS like 80x40", size);
+
+    let mut parts = size.split(['x', 'X']);
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(cols), Some(rows), None) => {
+            let cols = cols.trim().parse().map_err(|_| err())?;
+            let rows = rows.trim().parse().map_err(|_| err())?;
+            Ok((cols, rows))
+        }
+        _ => Err(err()),
+    }
+}
+
+/// Parses a region given as `r0,c0,r1,c1`, like `10,10,30,30`, into (r0, c0, r1, c1)
+fn parse_region(region: &str) -> Result<(usize, usize, usize, usize), String> {
+    let err = || format!("Invalid region '{}', expected r0,c0,r1,c1 like 10,10,30,30", region);
+    let bounds = region
+        .split(',')
+        .map(|n| n.trim().parse::<usize>().map_err(|_| err()))
+        .collect::<Result<Vec<usize>, String>>()?;
+    match bounds[..] {
+        [r0, c0, r1, c1] if r0 <= r1 && c0 <= c1 => Ok((r0, c0, r1, c1)),
+        _ => Err(err()),
+    }
+}
+
+/// returns the (cols, rows) of the biggest board that fits on a terminal of (`term_cols`, `term_rows`)
+/// along with its border and, if shown, the status line
+fn fit_size((term_cols, term_rows): (u16, u16), topology: Topology, status: bool) -> Option<(usize, usize)> {
+    let width = (term_cols as usize).checked_sub(2)?;
+    let rows = (term_rows as usize).checked_sub(2 + status as usize)?;
+    let cols = match topology {
+        Topology::Moore => width,
+        Topology::Hex => width.saturating_sub(1) / 2,
+    };
+    if rows == 0 || cols == 0 {
+        None
+    } else {
+        Some((cols, rows))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn size_parsing() {
+        assert_eq!(parse_size("80x40"), Ok((80, 40)));
+        assert_eq!(parse_size("3X2"), Ok((3, 2)));
+        assert!(parse_size("80x").is_err());
+        assert!(parse_size("axb").is_err());
+        assert!(parse_size("80").is_err());
+        assert!(parse_size("1x2x3").is_err());
+    }
+
+    #[test]
+    fn region_parsing() {
+        assert_eq!(parse_region("10,10,30,30"), Ok((10, 10, 30, 30)));
+        assert_eq!(parse_region("0, 5, 2, 8"), Ok((0, 5, 2, 8)));
+        assert!(parse_region("1,2,3").is_err());
+        assert!(parse_region("1,2,3,4,5").is_err());
+        assert!(parse_region("5,0,2,8").is_err());
+        assert!(parse_region("a,b,c,d").is_err());
+    }
+
+    #[test]
+    fn fit_to_terminal() {
+        assert_eq!(fit_size((80, 24), Topology::Moore, true), Some((78, 21)));
+        assert_eq!(fit_size((80, 24), Topology::Moore, false), Some((78, 22)));
+        assert_eq!(fit_size((80, 24), Topology::Hex, true), Some((38, 21)));
+        assert_eq!(fit_size((0, 0), Topology::Moore, true), None);
+        assert_eq!(fit_size((2, 3), Topology::Moore, true), None);
+    }
 }
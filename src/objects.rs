@@ -0,0 +1,210 @@
+/// A small catalog of well known Life objects for naming what's on a board
+///
+/// Objects are matched by shape in any rotation or reflection, so each one is only
+/// listed in a single orientation here. Oscillators and spaceships list every phase
+/// that is a single connected cluster of cells. Entries are (name, plaintext drawing).
+static CATALOG: &[(&str, &str)] = &[
+    ("block", "OO\nOO"),
+    ("beehive", ".OO.\nO..O\n.OO."),
+    ("loaf", ".OO.\nO..O\n.O.O\n..O."),
+    ("boat", "OO.\nO.O\n.O."),
+    ("ship", "OO.\nO.O\n.OO"),
+    ("tub", ".O.\nO.O\n.O."),
+    ("long boat", "OO..\nO.O.\n.O.O\n..O."),
+    ("pond", ".OO.\nO..O\nO..O\n.OO."),
+    ("blinker", "OOO"),
+    ("glider", ".O.\n..O\nOOO"),
+    ("glider", "O.O\n.OO\n.O."),
+    ("glider", "..O\nO.O\n.OO"),
+    ("glider", "O..\n.OO\nOO."),
+];
+
+/// How far a glider's bounding box moves over the next generation, as (rows, cols), for each of
+/// the catalog's glider phases in the orientation it's drawn in. They all fly down and right
+static GLIDER_STEPS: [(isize, isize); 4] = [(1, 0), (0, 0), (0, 1), (0, 0)];
+
+pub(crate) type Shape = Vec<(isize, isize)>;
+type Transform = fn((isize, isize)) -> (isize, isize);
+
+/// The 8 rotations and reflections of the square
+static TRANSFORMS: [Transform; 8] = [
+    |(r, c)| (r, c),
+    |(r, c)| (c, -r),
+    |(r, c)| (-r, -c),
+    |(r, c)| (-c, r),
+    |(r, c)| (r, -c),
+    |(r, c)| (-c, -r),
+    |(r, c)| (-r, c),
+    |(r, c)| (c, r),
+];
+
+/// Moves a shape so its top left corner is at the origin and puts the cells in a standard order
+pub(crate) fn normalize(mut cells: Shape) -> Shape {
+    let min_r = cells.iter().map(|(r, _)| *r).min().unwrap_or(0);
+    let min_c = cells.iter().map(|(_, c)| *c).min().unwrap_or(0);
+    for cell in cells.iter_mut() {
+        cell.0 -= min_r;
+        cell.1 -= min_c;
+    }
+    cells.sort_unstable();
+    cells
+}
+
+/// returns all 8 rotations and reflections of a shape, normalized
+fn orientations(cells: &[(isize, isize)]) -> Vec<Shape> {
+    TRANSFORMS
+        .iter()
+        .map(|t| normalize(cells.iter().map(|cell| t(*cell)).collect()))
+        .collect()
+}
+
+/// Parses one of the catalog's plaintext drawings
+fn parse_shape(drawing: &str) -> Shape {
+    drawing
+        .lines()
+        .enumerate()
+        .flat_map(|(r, line)| {
+            line.chars()
+                .enumerate()
+                .filter(|(_, ch)| *ch == 'O')
+                .map(move |(c, _)| (r as isize, c as isize))
+        })
+        .collect()
+}
+
+/// Names the object made up of exactly the given alive cells, if it's in the catalog
+pub(crate) fn identify(cells: &[(usize, usize)]) -> Option<&'static str> {
+    let shape = normalize(cells.iter().map(|(r, c)| (*r as isize, *c as isize)).collect());
+
+    CATALOG
+        .iter()
+        .find(|(_, drawing)| {
+            let known = parse_shape(drawing);
+            known.len() == shape.len() && orientations(&known).contains(&shape)
+        })
+        .map(|(name, _)| *name)
+}
+
+/// returns how far the bounding box of the glider made up of exactly the given alive cells
+/// moves over the next generation under Conway's rule, as (rows, cols), or `None` if the cells
+/// aren't a glider
+///
+/// A glider's phase and orientation tell which way it's flying, so this doesn't step anything.
+pub(crate) fn glider_step(cells: &[(usize, usize)]) -> Option<(isize, isize)> {
+    let shape = normalize(cells.iter().map(|(r, c)| (*r as isize, *c as isize)).collect());
+
+    CATALOG
+        .iter()
+        .filter(|(name, _)| *name == "glider")
+        .zip(GLIDER_STEPS.iter())
+        .find_map(|((_, drawing), step)| {
+            let known = parse_shape(drawing);
+            TRANSFORMS
+                .iter()
+                .find(|t| normalize(known.iter().map(|cell| t(*cell)).collect()) == shape)
+                .map(|t| t(*step))
+        })
+}
+
+/// Digits used by the extended Wechsler format, a column of up to 5 cells per character
+static WECHSLER_DIGITS: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+
+/// Encodes a normalized shape in the extended Wechsler format used by apgcodes
+///
+/// The shape is cut into strips 5 rows tall, separated by `z`. Each column of a strip is one
+/// character with the top row as the lowest bit, and runs of blank columns are shortened.
+fn wechsler(shape: &[(isize, isize)]) -> String {
+    let rows = shape.iter().map(|(r, _)| *r as usize + 1).max().unwrap_or(0);
+    let cols = shape.iter().map(|(_, c)| *c as usize + 1).max().unwrap_or(0);
+
+    let mut strips = Vec::new();
+    for strip in 0..rows.div_ceil(5) {
+        let mut columns = vec![0usize; cols];
+        for (r, c) in shape.iter().filter(|(r, _)| *r as usize / 5 == strip) {
+            columns[*c as usize] |= 1 << (*r as usize % 5);
+        }
+        while columns.last() == Some(&0) {
+            columns.pop();
+        }
+
+        let mut encoded = String::new();
+        let mut zeros = 0;
+        for value in columns.into_iter().chain(Some(usize::MAX)) {
+            if value == 0 {
+                zeros += 1;
+                continue;
+            }
+            while zeros >= 4 {
+                let run = zeros.min(39);
+                encoded.push('y');
+                encoded.push(WECHSLER_DIGITS[run - 4] as char);
+                zeros -= run;
+            }
+            encoded.push_str(["", "0", "w", "x"][zeros]);
+            zeros = 0;
+            if value != usize::MAX {
+                encoded.push(WECHSLER_DIGITS[value] as char);
+            }
+        }
+        strips.push(encoded);
+    }
+    strips.join("z")
+}
+
+/// Picks the canonical extended Wechsler encoding of an object: the shortest one, then the
+/// first alphabetically, over every given phase in all 8 orientations
+pub(crate) fn canonical_wechsler(phases: &[Shape]) -> String {
+    phases
+        .iter()
+        .flat_map(|phase| orientations(phase))
+        .map(|shape| wechsler(&shape))
+        .min_by(|a, b| a.len().cmp(&b.len()).then_with(|| a.cmp(b)))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn identify_in_any_orientation() {
+        // a vertical blinker
+        assert_eq!(identify(&[(5, 1), (6, 1), (7, 1)]), Some("blinker"));
+
+        // a boat pointing the other way
+        assert_eq!(identify(&[(0, 1), (1, 0), (1, 2), (2, 1), (2, 2)]), Some("boat"));
+
+        assert_eq!(identify(&[(0, 0), (0, 1)]), None);
+    }
+
+    #[test]
+    fn glider_steps_match_updates() {
+        use crate::board::Board;
+
+        for (_, drawing) in CATALOG.iter().filter(|(name, _)| *name == "glider") {
+            for shape in orientations(&parse_shape(drawing)) {
+                let mut b = Board::new(7, 7);
+                for (r, c) in shape.iter() {
+                    b.toggle_cell(*r as usize + 2, *c as usize + 2).unwrap();
+                }
+                let cells: Vec<(usize, usize)> = b.iter_cells().filter(|(_, a)| **a).map(|(rc, _)| rc).collect();
+                let (top, left, _, _) = b.live_bounding_box().unwrap();
+                b.update();
+                let (next_top, next_left, _, _) = b.live_bounding_box().unwrap();
+                let moved = (next_top as isize - top as isize, next_left as isize - left as isize);
+                assert_eq!(glider_step(&cells), Some(moved), "{:?}", shape);
+            }
+        }
+        assert_eq!(glider_step(&[(0, 0), (0, 1), (1, 0), (1, 1)]), None);
+    }
+
+    #[test]
+    fn wechsler_encoding() {
+        assert_eq!(wechsler(&parse_shape("OO\nOO")), "33");
+
+        // a blank column in the middle and a second strip
+        assert_eq!(wechsler(&parse_shape("O.O")), "101");
+        assert_eq!(wechsler(&parse_shape("O......O")), "1y21");
+        assert_eq!(wechsler(&parse_shape("O\n.\n.\n.\n.\nO")), "1z1");
+    }
+}
@@ -0,0 +1,15 @@
+/// Classic patterns built into the crate so demos don't need any files
+///
+/// Entries are (name, RLE), parsed on demand by `Configuration::builtin`.
+pub(crate) static BUILTIN: &[(&str, &str)] = &[
+    ("glider", "x = 3, y = 3, rule = B3/S23\nbo$2bo$3o!"),
+    ("blinker", "x = 3, y = 1, rule = B3/S23\n3o!"),
+    (
+        "glider-gun",
+        "x = 36, y = 9, rule = B3/S23
+24bo$22bobo$12b2o6b2o12b2o$11bo3bo4b2o12b2o$2o8bo5bo3b2o$2o8bo3bob2o4b
+obo$10bo5bo7bo$11bo3bo$12b2o!",
+    ),
+    ("r-pentomino", "x = 3, y = 3, rule = B3/S23\nb2o$2o$bo!"),
+    ("acorn", "x = 7, y = 3, rule = B3/S23\nbo$3bo$2o2b3o!"),
+];
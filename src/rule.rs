@@ -0,0 +1,445 @@
+/// Life-like rules in birth/survival notation
+///
+/// Conway's Game of Life is `B3/S23`: a dead cell with exactly three alive
+/// neighbors is born, and an alive cell with two or three alive neighbors survives.
+/// Generations rules like Brian's Brain, `B2/S/C3`, add dying states that cells
+/// pass through on their way from alive to dead, and history rules like `B3/S23/H2`
+/// stop cells that were alive recently from being born again.
+///
+use core::fmt::{Display, Formatter, Error};
+#[cfg(feature = "std")]
+use std::str::FromStr;
+
+/// Reasons a rule string couldn't be parsed
+#[cfg(feature = "std")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RuleError {
+    /// The rule is an isotropic non-totalistic one like `B2ac/S12`, which depends
+    /// on the arrangement of the neighbors and not just how many there are
+    NonTotalisticUnsupported(String),
+    /// The rule isn't in a format we understand
+    Invalid(String),
+}
+
+#[cfg(feature = "std")]
+impl Display for RuleError {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
+        match self {
+            RuleError::NonTotalisticUnsupported(s) => write!(
+                f,
+                "Unsupported rule: {} is non-totalistic, only totalistic rules like B3/S23 can be played",
+                s
+            ),
+            RuleError::Invalid(s) => write!(f, "Unsupported rule: {}", s),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for RuleError {}
+
+/// A rule is stored as which neighbor counts cause birth and survival, not as the string it
+/// was parsed from, so two rules are equal whenever they play the same, whatever notation
+/// they were written in.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Rule {
+    /// A Life-like or Generations rule, where a cell's next state only depends on its state and
+    /// how many of its neighbors are alive
+    Totalistic(Totalistic),
+    /// A totalistic rule where cells also remember whether they were alive in each of the last
+    /// `window` generations, up to 32. A dead cell that was alive in `refractory` or more of
+    /// them can't be born, which damps down patterns that flicker on and off. Written as the
+    /// base rule followed by `/H<window>`, or `/H<window>:<refractory>` if `refractory` isn't 1
+    ///
+    /// Only `Board` keeps the history these rules need. `Grid` plays them like their base rule.
+    History { base: Totalistic, window: u8, refractory: u8 },
+}
+
+/// Which neighbor counts cause birth and survival, and how many states cells pass through
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Totalistic {
+    /// `birth[n]` is true if a dead cell with `n` alive neighbors becomes alive
+    birth: [bool; 9],
+    /// `survival[n]` is true if an alive cell with `n` alive neighbors stays alive
+    survival: [bool; 9],
+    /// Number of states a cell can be in, including dead and alive. Anything over 2 are dying states
+    states: u8,
+}
+
+impl Totalistic {
+    fn next_state(&self, is_alive: bool, alive_neighbors: u32) -> bool {
+        let n = alive_neighbors as usize;
+        if n >= 9 {
+            return false;
+        }
+        if is_alive {
+            self.survival[n]
+        } else {
+            self.birth[n]
+        }
+    }
+
+    fn next_cell_state(&self, state: u8, alive_neighbors: u32) -> u8 {
+        match state {
+            0 | 1 if self.next_state(state == 1, alive_neighbors) => 1,
+            // alive cells that don't survive start dying, if the rule has dying states
+            1 if self.states > 2 => 2,
+            // dying cells keep going until they run out of states
+            s if s > 1 && s + 1 < self.states => s + 1,
+            _ => 0,
+        }
+    }
+}
+
+impl Rule {
+    /// returns the standard Conway's Game of Life rule, B3/S23
+    pub fn conway() -> Rule {
+        Rule::generations(&[3], &[2, 3], 2).unwrap()
+    }
+
+    /// returns a Generations rule: dead cells with a number of alive neighbors in `birth` are
+    /// born, alive cells with a number in `survival` stay alive, and the rest pass through
+    /// `states - 2` dying states on their way to dead. With 2 states this is a Life-like rule.
+    ///
+    /// returns `None` if a count is over 8 or there are fewer than 2 states.
+    pub fn generations(birth: &[u32], survival: &[u32], states: u8) -> Option<Rule> {
+        if states < 2 || birth.iter().chain(survival).any(|n| *n > 8) {
+            return None;
+        }
+        let mut r = Totalistic {birth: [false; 9], survival: [false; 9], states};
+        for n in birth {
+            r.birth[*n as usize] = true;
+        }
+        for n in survival {
+            r.survival[*n as usize] = true;
+        }
+        Some(Rule::Totalistic(r))
+    }
+
+    /// returns `base` played as a history rule, see `Rule::History`
+    ///
+    /// returns `None` if `base` already has a history, `window` isn't between 1 and 32, or
+    /// `refractory` isn't between 1 and `window`.
+    pub fn history(base: Rule, window: u8, refractory: u8) -> Option<Rule> {
+        match base {
+            Rule::Totalistic(base) if (1..=32).contains(&window) && (1..=window).contains(&refractory) => {
+                Some(Rule::History {base, window, refractory})
+            }
+            _ => None,
+        }
+    }
+
+    /// true if this is the standard Conway's Game of Life rule
+    pub fn is_conway(&self) -> bool {
+        *self == Rule::conway()
+    }
+
+    /// returns the number of states a cell can be in. 2 for Life-like rules
+    pub fn states(&self) -> u8 {
+        self.base().states
+    }
+
+    /// returns how many generations back a cell's history goes, 0 for rules without one
+    pub fn history_window(&self) -> u32 {
+        match self {
+            Rule::Totalistic(_) => 0,
+            Rule::History {window, ..} => u32::from(*window).min(32),
+        }
+    }
+
+    /// returns the bits of a cell's history the rule looks at
+    pub(crate) fn history_mask(&self) -> u32 {
+        match self.history_window() {
+            32 => u32::MAX,
+            window => (1 << window) - 1,
+        }
+    }
+
+    /// given whether a cell is alive and its number of alive neighbors,
+    /// returns whether it will be alive next generation
+    ///
+    /// History rules answer as if the cell had no history.
+    pub fn next_state(&self, is_alive: bool, alive_neighbors: u32) -> bool {
+        self.base().next_state(is_alive, alive_neighbors)
+    }
+
+    /// given a cell's state (0 for dead, 1 for alive and 2 and up for dying) and its number
+    /// of alive neighbors, returns its state next generation
+    ///
+    /// History rules answer as if the cell had no history.
+    pub fn next_cell_state(&self, state: u8, alive_neighbors: u32) -> u8 {
+        self.base().next_cell_state(state, alive_neighbors)
+    }
+
+    /// Like `next_cell_state`, but for a cell with `history`, which has a bit set for each of
+    /// the previous generations it was alive in, the last one in the lowest bit
+    pub fn next_cell_state_with_history(&self, state: u8, alive_neighbors: u32, history: u32) -> u8 {
+        let next = self.base().next_cell_state(state, alive_neighbors);
+        match self {
+            Rule::History {refractory, ..}
+                if state == 0 && next == 1 && (history & self.history_mask()).count_ones() >= u32::from(*refractory) => 0,
+            _ => next,
+        }
+    }
+
+    /// returns the totalistic part of the rule
+    fn base(&self) -> &Totalistic {
+        match self {
+            Rule::Totalistic(base) | Rule::History {base, ..} => base,
+        }
+    }
+}
+
+impl Default for Rule {
+    fn default() -> Rule {
+        Rule::conway()
+    }
+}
+
+/// Letters used by isotropic non-totalistic rules to say which arrangements of neighbors count
+#[cfg(feature = "std")]
+static NON_TOTALISTIC_LETTERS: &str = "cekainyqjrtwz-";
+
+/// true if a birth or survival part of a rule, like `2ac`, is non-totalistic
+#[cfg(feature = "std")]
+fn is_non_totalistic(part: &str) -> bool {
+    if part.starts_with(['c', 'C']) {
+        return false;
+    }
+    let counts = part.trim_start_matches(['b', 'B', 's', 'S']);
+    counts.chars().any(|c| c.is_ascii_digit())
+        && counts.chars().any(|c| NON_TOTALISTIC_LETTERS.contains(c.to_ascii_lowercase()))
+}
+
+/// Parses a list of neighbor counts like `23` into a lookup table
+#[cfg(feature = "std")]
+fn parse_counts(counts: &str) -> Option<[bool; 9]> {
+    let mut table = [false; 9];
+    for ch in counts.chars() {
+        match ch.to_digit(10) {
+            Some(d) if d <= 8 => table[d as usize] = true,
+            _ => return None,
+        }
+    }
+    Some(table)
+}
+
+#[cfg(feature = "std")]
+impl FromStr for Rule {
+    type Err = RuleError;
+
+    /// Parses `B3/S23` style rules (in either order) as well as the older `23/3` S/B notation.
+    /// Generations rules add the number of states, as in `B2/S/C3` or `/2/3`, and history rules
+    /// end with their window and refractory count, as in `B3/S23/H4:2`.
+    fn from_str(s: &str) -> Result<Rule, RuleError> {
+        let err = || RuleError::Invalid(s.to_string());
+
+        let mut parts: Vec<&str> = s.trim().split('/').collect();
+        let history = match parts.last() {
+            Some(last) if parts.len() > 2 && last.starts_with(['h', 'H']) => {
+                let (window, refractory) = match last[1..].split_once(':') {
+                    Some((window, refractory)) => (window, refractory),
+                    None => (&last[1..], "1"),
+                };
+                match (window.parse::<u8>(), refractory.parse::<u8>()) {
+                    (Ok(window), Ok(refractory)) => Some((window, refractory)),
+                    _ => return Err(err()),
+                }
+            }
+            _ => None,
+        };
+        if history.is_some() {
+            parts.pop();
+        }
+        if parts.len() != 2 && parts.len() != 3 {
+            return Err(err());
+        }
+        if parts.iter().any(|p| is_non_totalistic(p)) {
+            return Err(RuleError::NonTotalisticUnsupported(s.to_string()));
+        }
+
+        let parse_states = |part: &str| part.parse::<u8>().ok().filter(|n| *n >= 2);
+        let mut birth = None;
+        let mut survival = None;
+        let mut states = Some(2);
+        for part in &parts {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some('b') | Some('B') => birth = parse_counts(chars.as_str()),
+                Some('s') | Some('S') => survival = parse_counts(chars.as_str()),
+                Some('c') | Some('C') | Some('g') | Some('G') => states = parse_states(chars.as_str()),
+                _ => {
+                    // no prefixes, so this is S/B notation with the states last
+                    survival = parse_counts(parts[0]);
+                    birth = parse_counts(parts[1]);
+                    if let Some(n) = parts.get(2) {
+                        states = parse_states(n);
+                    }
+                    break;
+                }
+            }
+        }
+
+        let rule = match (birth, survival, states) {
+            (Some(birth), Some(survival), Some(states)) => Rule::Totalistic(Totalistic {birth, survival, states}),
+            _ => return Err(err()),
+        };
+        match history {
+            Some((window, refractory)) => Rule::history(rule, window, refractory).ok_or_else(err),
+            None => Ok(rule),
+        }
+    }
+}
+
+impl Display for Rule {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
+        write!(f, "{}", self.base())?;
+        match self {
+            Rule::History {window, refractory: 1, ..} => write!(f, "/H{}", window),
+            Rule::History {window, refractory, ..} => write!(f, "/H{}:{}", window, refractory),
+            Rule::Totalistic(_) => Ok(()),
+        }
+    }
+}
+
+impl Display for Totalistic {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
+        write!(f, "B")?;
+        for (n, born) in self.birth.iter().enumerate() {
+            if *born {
+                write!(f, "{}", n)?;
+            }
+        }
+        write!(f, "/S")?;
+        for (n, survives) in self.survival.iter().enumerate() {
+            if *survives {
+                write!(f, "{}", n)?;
+            }
+        }
+        if self.states > 2 {
+            write!(f, "/C{}", self.states)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_conway() {
+        assert!("B3/S23".parse::<Rule>().unwrap().is_conway());
+        assert!("b3/s23".parse::<Rule>().unwrap().is_conway());
+        assert!("S23/B3".parse::<Rule>().unwrap().is_conway());
+        assert!("23/3".parse::<Rule>().unwrap().is_conway());
+    }
+
+    #[test]
+    fn generations_constructor() {
+        // Star Wars
+        let r = Rule::generations(&[2], &[3, 4, 5], 4).unwrap();
+        assert_eq!(r, "345/2/4".parse().unwrap());
+        assert_eq!(r.to_string(), "B2/S345/C4");
+        assert_eq!(Rule::generations(&[3], &[2, 3], 2), Some(Rule::conway()));
+        assert_eq!(Rule::generations(&[9], &[], 3), None);
+        assert_eq!(Rule::generations(&[2], &[], 1), None);
+    }
+
+    #[test]
+    fn equal_across_notations() {
+        let rule = |s: &str| s.parse::<Rule>().unwrap();
+        for same in &["B36/S23", "b36/s23", "S23/B36", "23/36", "B63/S32", "B36/S23/C2", "23/36/2"] {
+            assert_eq!(rule(same), rule("B36/S23"), "{}", same);
+        }
+        assert_eq!(rule("B2/S/C3"), rule("/2/3"));
+        assert_eq!(rule("B2/S/G3"), rule("/2/3"));
+        assert_eq!(rule("B2/S345/C4"), rule("345/2/4"));
+
+        assert_ne!(rule("B3/S23"), rule("3/23"));
+        assert_ne!(rule("B2/S/C3"), rule("B2/S/C4"));
+        assert_ne!(rule("B2/S"), rule("B2/S/C3"));
+    }
+
+    #[test]
+    fn parse_highlife() {
+        let r: Rule = "B36/S23".parse().unwrap();
+        assert!(!r.is_conway());
+        assert!(r.next_state(false, 6));
+        assert!(!r.next_state(true, 6));
+        assert_eq!(r.to_string(), "B36/S23");
+    }
+
+    #[test]
+    fn parse_invalid() {
+        assert!("B9/S23".parse::<Rule>().is_err());
+        assert!("B3/S23/X3".parse::<Rule>().is_err());
+        assert!("B3/S23/C1".parse::<Rule>().is_err());
+        assert!("life".parse::<Rule>().is_err());
+    }
+
+    #[test]
+    fn parse_generations() {
+        let r: Rule = "B2/S/C3".parse().unwrap();
+        assert_eq!(r.states(), 3);
+        assert!(r.next_state(false, 2));
+        assert!(!r.next_state(true, 2));
+        assert_eq!(r.to_string(), "B2/S/C3");
+
+        assert_eq!("/2/3".parse::<Rule>(), Ok(r));
+        assert_eq!("B3/S23/C2".parse::<Rule>(), Ok(Rule::conway()));
+    }
+
+    #[test]
+    fn parse_non_totalistic() {
+        assert_eq!(
+            "B2ac/S12".parse::<Rule>(),
+            Err(RuleError::NonTotalisticUnsupported(String::from("B2ac/S12")))
+        );
+        assert_eq!(
+            "B3/S2-a3".parse::<Rule>(),
+            Err(RuleError::NonTotalisticUnsupported(String::from("B3/S2-a3")))
+        );
+        assert_eq!("B2/S34H".parse::<Rule>(), Err(RuleError::Invalid(String::from("B2/S34H"))));
+    }
+
+    #[test]
+    fn parse_history() {
+        let r: Rule = "B3/S23/H2".parse().unwrap();
+        assert_eq!(r, Rule::history(Rule::conway(), 2, 1).unwrap());
+        assert!(matches!(r, Rule::History {window: 2, ..}));
+        assert!(!r.is_conway());
+        assert_eq!(r.history_window(), 2);
+        assert_eq!(r.to_string(), "B3/S23/H2");
+        assert_eq!("23/3/h2:1".parse::<Rule>(), Ok(r));
+
+        let brain: Rule = "B2/S/C3/H4:2".parse().unwrap();
+        assert_eq!(brain.states(), 3);
+        assert_eq!(brain.to_string(), "B2/S/C3/H4:2");
+        assert_eq!(Rule::conway().history_window(), 0);
+
+        for invalid in &["B3/S23/H0", "B3/S23/H33", "B3/S23/H2:3", "B3/S23/H2:0", "B3/S23/Hx", "B3/H2"] {
+            assert!(invalid.parse::<Rule>().is_err(), "{}", invalid);
+        }
+        assert_eq!(Rule::history(r, 2, 1), None);
+    }
+
+    #[test]
+    fn history_blocks_births() {
+        let r = Rule::history(Rule::conway(), 3, 2).unwrap();
+        assert_eq!(r.next_cell_state_with_history(0, 3, 0b001), 1);
+        assert_eq!(r.next_cell_state_with_history(0, 3, 0b101), 0);
+        // only the last `window` generations count
+        assert_eq!(r.next_cell_state_with_history(0, 3, 0b1001), 1);
+        // and only births are blocked
+        assert_eq!(r.next_cell_state_with_history(1, 2, 0b111), 1);
+        assert_eq!(Rule::conway().next_cell_state_with_history(0, 3, u32::MAX), 1);
+
+        // dying states are kept
+        let brain = Rule::history("B2/S/C3".parse().unwrap(), 1, 1).unwrap();
+        assert_eq!(brain.next_cell_state_with_history(1, 2, 0), 2);
+        assert_eq!(brain.next_cell_state_with_history(2, 2, 1), 0);
+        assert_eq!(brain.next_cell_state_with_history(0, 2, 1), 0);
+        assert_eq!(brain.next_cell_state_with_history(0, 2, 0), 1);
+    }
+}
@@ -0,0 +1,205 @@
+/// A board that only stores its alive cells
+///
+/// Huge patterns are mostly empty space, so keeping just the alive cells lets
+/// them be loaded without building the full grid of cells.
+///
+use std::collections::HashSet;
+use std::io::{BufRead, BufReader, Read};
+
+use crate::board::Board;
+use crate::config;
+use crate::rule::Rule;
+
+#[derive(Clone, Debug)]
+pub struct SparseBoard {
+    pub rows: usize,
+    pub cols: usize,
+
+    /// The rule used to compute the next generation. Defaults to Conway's B3/S23
+    pub rule: Rule,
+
+    /// (row, col) of every alive cell
+    cells: HashSet<(usize, usize)>,
+}
+
+impl SparseBoard {
+    /// Initializes a new board of the given dimensions with all cells dead
+    pub fn new(rows: usize, cols: usize) -> SparseBoard {
+        SparseBoard {
+            rows,
+            cols,
+            rule: Rule::conway(),
+            cells: HashSet::new(),
+        }
+    }
+
+    /// Reads a pattern in the standard RLE format a line at a time, only keeping its alive cells
+    ///
+    /// Unlike `Configuration::load_rle_config`, the whole file is never held in memory,
+    /// so this works for patterns that are far too big to load as a dense board.
+    pub fn from_rle_reader<R: Read>(r: R) -> Result<SparseBoard, &'static str> {
+        let mut reader = BufReader::new(r);
+        let header = config::read_rle_header(&mut reader)?;
+        if header.rows == 0 || header.cols == 0 {
+            return Err("Invalid board dimensions!");
+        }
+        let mut b = SparseBoard::new(header.rows, header.cols);
+
+        // where the next cell goes, and the run count read so far
+        let mut row: usize = 0;
+        let mut col: usize = 0;
+        let mut count: Option<usize> = None;
+
+        for line in reader.lines() {
+            let line = line.map_err(|_| "Couldn't read the RLE pattern!")?;
+            let line = line.trim();
+            if line.starts_with('#') {
+                continue;
+            }
+
+            for ch in line.chars() {
+                if let Some(d) = ch.to_digit(10) {
+                    // the file may not be trusted, so a run count too big to hold is an error
+                    let run = count.unwrap_or(0).checked_mul(10).and_then(|n| n.checked_add(d as usize));
+                    count = Some(run.ok_or("Invalid run count in RLE board!")?);
+                    continue;
+                }
+                let n = count.take().unwrap_or(1);
+                match ch {
+                    'b' | '.' => col = col.checked_add(n).ok_or("Invalid run count in RLE board!")?,
+                    'o' | 'A' => {
+                        let end = col.checked_add(n).ok_or("Invalid run count in RLE board!")?;
+                        if row >= b.rows || end > b.cols {
+                            return Err("Board description exceeds the given dimensions!");
+                        }
+                        for _ in 0..n {
+                            b.cells.insert((row, col));
+                            col += 1;
+                        }
+                    }
+                    '$' => {
+                        row = row.checked_add(n).ok_or("Invalid run count in RLE board!")?;
+                        col = 0;
+                    }
+                    '!' => return b.with_rule(header.rule),
+                    c if c.is_whitespace() => {}
+                    _ => return Err("Invalid character in RLE board!"),
+                }
+            }
+        }
+
+        b.with_rule(header.rule)
+    }
+
+    /// Parses a pattern in the Life 1.06 format, moving it so its top left alive cell is
+    /// at (0, 0). Unlike `Configuration::from_life106_str`, there's no limit on how far
+    /// apart the cells can be.
+    pub fn from_life106_str(s: &str) -> Result<SparseBoard, &'static str> {
+        let cells = config::parse_life106_string(s)?;
+        let (top, left, rows, cols) = config::life106_bounds(&cells).ok_or("Configuration does not describe a board!")?;
+
+        let mut board = SparseBoard::new(rows, cols);
+        board.cells = cells
+            .into_iter()
+            .map(|(r, c)| ((r - top) as usize, (c - left) as usize))
+            .collect();
+        Ok(board)
+    }
+
+    /// Puts the rule on a board read by `from_rle_reader`
+    fn with_rule(mut self, rule: Option<String>) -> Result<SparseBoard, &'static str> {
+        if let Some(rule) = rule {
+            self.rule = rule.parse().map_err(|_| "Unsupported rule in RLE pattern!")?;
+        }
+        Ok(self)
+    }
+
+    /// returns whether the cell at (`row`, `col`) is alive
+    pub fn is_alive(&self, row: usize, col: usize) -> bool {
+        self.cells.contains(&(row, col))
+    }
+
+    /// returns the number of alive cells on the board.
+    pub fn get_num_alive_cells(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Iterates over the (row, col) of every alive cell, in no particular order
+    pub fn live_cells(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.cells.iter().copied()
+    }
+
+    /// Builds a regular dense board with the same cells and rule
+    pub fn to_board(&self) -> Board {
+        let mut board = Board::new(self.rows, self.cols);
+        board.rule = self.rule;
+        for &(r, c) in &self.cells {
+            board.grid[r][c].is_alive = true;
+        }
+        board
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn read_glider() {
+        let rle = "#N Glider
+#R B36/S23
+x = 3, y = 3
+bo$2bo$3o!";
+        let b = SparseBoard::from_rle_reader(rle.as_bytes()).unwrap();
+        assert_eq!((b.rows, b.cols), (3, 3));
+        assert_eq!(b.rule.to_string(), "B36/S23");
+        assert_eq!(b.get_num_alive_cells(), 5);
+        assert!(b.is_alive(0, 1) && b.is_alive(1, 2) && b.is_alive(2, 0));
+        assert!(!b.is_alive(0, 0));
+
+        let dense = b.to_board();
+        assert_eq!(dense.get_num_alive_cells(), 5);
+        assert_eq!(dense.rule, b.rule);
+    }
+
+    #[test]
+    fn read_runs_across_lines() {
+        // the header rule wins over the comment, and a run count can be split across lines
+        let rle = "#r 23/36
+x = 12, y = 2, rule = B3/S23
+1
+2o$
+o!";
+        let b = SparseBoard::from_rle_reader(rle.as_bytes()).unwrap();
+        assert!(b.rule.is_conway());
+        assert_eq!(b.get_num_alive_cells(), 13);
+        assert!(b.is_alive(0, 11) && b.is_alive(1, 0));
+    }
+
+    #[test]
+    fn read_life106_far_apart() {
+        let b = SparseBoard::from_life106_str("#Life 1.06\n-1000000000 0\n1000000000 5000000").unwrap();
+        assert_eq!((b.rows, b.cols), (5000001, 2000000001));
+        assert!(b.is_alive(0, 0) && b.is_alive(5000000, 2000000000));
+        assert_eq!(b.get_num_alive_cells(), 2);
+    }
+
+    #[test]
+    fn read_invalid() {
+        assert!(SparseBoard::from_rle_reader("x = 2, y = 2\n3o!".as_bytes()).is_err());
+        assert!(SparseBoard::from_rle_reader("x = 2, y = 2\n2o$2o$2o!".as_bytes()).is_err());
+        assert!(SparseBoard::from_rle_reader("2o!".as_bytes()).is_err());
+        assert!(SparseBoard::from_rle_reader("".as_bytes()).is_err());
+        assert!(SparseBoard::from_rle_reader("x = 2, y = 2\n2q!".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn read_oversized_run_counts() {
+        let invalid = Err("Invalid run count in RLE board!");
+        let read = |rle: &str| SparseBoard::from_rle_reader(rle.as_bytes()).map(|b| b.cells.len());
+        assert_eq!(read("x = 2, y = 2\n99999999999999999999999o!"), invalid);
+        assert_eq!(read(&format!("x = 2, y = 2\n{}b{}o!", usize::MAX, usize::MAX)), invalid);
+        assert_eq!(read(&format!("x = 2, y = 2\nb{}o!", usize::MAX)), invalid);
+        assert_eq!(read(&format!("x = 2, y = 2\n$o{}$o!", usize::MAX)), invalid);
+    }
+}
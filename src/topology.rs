@@ -0,0 +1,178 @@
+/// Neighborhoods a cell can have on the board, and what's past its edges
+///
+#[cfg(feature = "std")]
+use std::str::FromStr;
+
+/// (row, col) offsets of the 8 cells surrounding a cell on a square grid
+static MOORE: [(isize, isize); 8] = [
+    (-1, -1), (-1, 0), (-1, 1),
+    (0, -1),           (0, 1),
+    (1, -1),  (1, 0),  (1, 1),
+];
+
+/// Hex neighbors of a cell in an even row. Odd rows are shifted half a cell to the right
+static HEX_EVEN_ROW: [(isize, isize); 6] = [
+    (-1, -1), (-1, 0),
+    (0, -1),  (0, 1),
+    (1, -1),  (1, 0),
+];
+
+/// Hex neighbors of a cell in an odd row
+static HEX_ODD_ROW: [(isize, isize); 6] = [
+    (-1, 0), (-1, 1),
+    (0, -1), (0, 1),
+    (1, 0),  (1, 1),
+];
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Topology {
+    /// The standard square grid where every cell has 8 neighbors
+    #[default]
+    Moore,
+    /// A hexagonal grid where every cell has 6 neighbors. It's stored as a rectangular
+    /// grid where every odd row is shifted half a cell to the right.
+    Hex,
+}
+
+impl Topology {
+    /// returns the (row, col) offsets of the neighbors of a cell in the given row
+    pub fn neighbor_offsets(&self, row: usize) -> &'static [(isize, isize)] {
+        match self {
+            Topology::Moore => &MOORE,
+            Topology::Hex if row % 2 == 0 => &HEX_EVEN_ROW,
+            Topology::Hex => &HEX_ODD_ROW,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl FromStr for Topology {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Topology, String> {
+        match s.to_ascii_lowercase().as_str() {
+            "moore" | "square" => Ok(Topology::Moore),
+            "hex" | "hexagonal" => Ok(Topology::Hex),
+            _ => Err(format!("Unknown topology: {}", s)),
+        }
+    }
+}
+
+/// What a cell sees when it looks past the edge of the board
+///
+/// The board's left and right edges are columns `0` and `cols - 1`, its top and
+/// bottom edges are rows `0` and `rows - 1`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum EdgeMode {
+    /// Everything past the edges is dead
+    #[default]
+    Dead,
+    /// The left edge is joined to the right edge and the top edge to the bottom edge,
+    /// so patterns leaving one side come back on the other
+    Torus,
+    /// The left edge is joined to the right edge with a flip: leaving the right side in
+    /// row `r` comes back on the left side in row `rows - 1 - r`. The top and bottom are dead
+    Mobius,
+    /// The left and right edges are joined with a flip like `Mobius`, and the top edge
+    /// is joined to the bottom edge like `Torus`
+    Klein,
+    /// Every edge is a mirror between the last cell on the board and the first one past it,
+    /// so the cell just past an edge is a copy of the cell just inside it: row `-1` is row `0`
+    /// and row `rows` is row `rows - 1`, and the same for columns. Past a corner both are
+    /// mirrored, so `(-1, -1)` is the corner cell `(0, 0)`. Cells along the edges count
+    /// themselves as a neighbor this way
+    Reflective,
+}
+
+impl EdgeMode {
+    /// Maps a location that may be off the board back onto it, or `None` if it's past a dead edge
+    pub fn resolve(&self, row: isize, col: isize, rows: usize, cols: usize) -> Option<(usize, usize)> {
+        let (rows, cols) = (rows as isize, cols as isize);
+        let mut r = row;
+        let mut c = col;
+
+        // top and bottom
+        if r < 0 || r >= rows {
+            match self {
+                EdgeMode::Torus | EdgeMode::Klein => r = r.rem_euclid(rows),
+                EdgeMode::Reflective => r = reflect(r, rows)?,
+                EdgeMode::Dead | EdgeMode::Mobius => return None,
+            }
+        }
+
+        // left and right
+        if c < 0 || c >= cols {
+            match self {
+                EdgeMode::Dead => return None,
+                EdgeMode::Reflective => c = reflect(c, cols)?,
+                EdgeMode::Torus => c = c.rem_euclid(cols),
+                EdgeMode::Mobius | EdgeMode::Klein => {
+                    c = c.rem_euclid(cols);
+                    r = rows - 1 - r;
+                }
+            }
+        }
+
+        Some((r as usize, c as usize))
+    }
+}
+
+/// Mirrors an index that is off either end of `0..n` back onto it, or `None` if it's so far
+/// off that it's past the other end too
+fn reflect(i: isize, n: isize) -> Option<isize> {
+    let i = if i < 0 { -1 - i } else { 2 * n - 1 - i };
+    if (0..n).contains(&i) {
+        Some(i)
+    } else {
+        None
+    }
+}
+
+#[cfg(feature = "std")]
+impl FromStr for EdgeMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<EdgeMode, String> {
+        match s.to_ascii_lowercase().as_str() {
+            "dead" => Ok(EdgeMode::Dead),
+            "torus" | "wrap" => Ok(EdgeMode::Torus),
+            "mobius" => Ok(EdgeMode::Mobius),
+            "klein" => Ok(EdgeMode::Klein),
+            "reflective" | "mirror" => Ok(EdgeMode::Reflective),
+            _ => Err(format!("Unknown edge mode: {}", s)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn resolve_edges() {
+        // on the board is always the same spot
+        for mode in &[EdgeMode::Dead, EdgeMode::Torus, EdgeMode::Mobius, EdgeMode::Klein, EdgeMode::Reflective] {
+            assert_eq!(mode.resolve(1, 2, 4, 5), Some((1, 2)));
+        }
+
+        assert_eq!(EdgeMode::Dead.resolve(-1, 0, 4, 5), None);
+        assert_eq!(EdgeMode::Torus.resolve(-1, 5, 4, 5), Some((3, 0)));
+
+        // off the right edge comes back flipped on the left
+        assert_eq!(EdgeMode::Mobius.resolve(0, 5, 4, 5), Some((3, 0)));
+        assert_eq!(EdgeMode::Mobius.resolve(1, -1, 4, 5), Some((2, 4)));
+        assert_eq!(EdgeMode::Mobius.resolve(4, 0, 4, 5), None);
+
+        assert_eq!(EdgeMode::Klein.resolve(4, 0, 4, 5), Some((0, 0)));
+        assert_eq!(EdgeMode::Klein.resolve(4, 5, 4, 5), Some((3, 0)));
+
+        // past the edge is the cell just inside it, and past a corner is the corner
+        assert_eq!(EdgeMode::Reflective.resolve(-1, 2, 4, 5), Some((0, 2)));
+        assert_eq!(EdgeMode::Reflective.resolve(4, 2, 4, 5), Some((3, 2)));
+        assert_eq!(EdgeMode::Reflective.resolve(1, 5, 4, 5), Some((1, 4)));
+        assert_eq!(EdgeMode::Reflective.resolve(-1, -1, 4, 5), Some((0, 0)));
+        assert_eq!(EdgeMode::Reflective.resolve(4, 5, 4, 5), Some((3, 4)));
+        assert_eq!(EdgeMode::Reflective.resolve(-2, 0, 4, 5), Some((1, 0)));
+        assert_eq!(EdgeMode::Reflective.resolve(-1, 0, 0, 0), None);
+    }
+}
@@ -0,0 +1,16 @@
+use std::path::Path;
+
+use gameoflife::app;
+
+#[test]
+fn all_board_configs_load_and_run() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("board_configs");
+    let results = app::test_patterns(&dir, 50).unwrap();
+
+    assert!(!results.is_empty(), "No patterns found in {}", dir.display());
+    for (path, result) in results {
+        if let Err(why) = result {
+            panic!("{} failed: {}", path.display(), why);
+        }
+    }
+}